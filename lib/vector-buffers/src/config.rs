@@ -11,6 +11,8 @@ use tracing::Span;
 use vector_common::{config::ComponentKey, finalization::Finalizable};
 use vector_config::configurable_component;
 
+#[cfg(feature = "sqlite")]
+use crate::variants::SqliteBuffer;
 use crate::{
     Bufferable, WhenFull,
     topology::{
@@ -36,6 +38,9 @@ enum BufferTypeKind {
     Memory,
     #[serde(rename = "disk")]
     DiskV2,
+    #[cfg(feature = "sqlite")]
+    #[serde(rename = "sqlite")]
+    Sqlite,
 }
 
 const ALL_FIELDS: [&str; 4] = ["type", "max_events", "max_size", "when_full"];
@@ -125,6 +130,19 @@ impl BufferTypeVisitor {
                     when_full,
                 })
             }
+            #[cfg(feature = "sqlite")]
+            BufferTypeKind::Sqlite => {
+                if max_events.is_some() {
+                    return Err(de::Error::unknown_field(
+                        "max_events",
+                        &["type", "max_size", "when_full"],
+                    ));
+                }
+                Ok(BufferType::Sqlite {
+                    max_size: max_size.ok_or_else(|| de::Error::missing_field("max_size"))?,
+                    when_full,
+                })
+            }
         }
     }
 }
@@ -251,6 +269,23 @@ pub enum BufferType {
         #[serde(default)]
         when_full: WhenFull,
     },
+
+    /// A buffer stage backed by a SQLite database on disk.
+    ///
+    /// This is less performant than the `disk` buffer, but each event is committed to the
+    /// database as it is written, and the buffered events can be inspected with standard SQLite
+    /// tooling. Events are deleted from the database once they are acknowledged.
+    #[cfg(feature = "sqlite")]
+    #[configurable(title = "Events are buffered in a SQLite database.")]
+    Sqlite {
+        /// The maximum size of the events in the database.
+        #[configurable(metadata(docs::type_unit = "bytes"))]
+        max_size: NonZeroU64,
+
+        #[configurable(derived)]
+        #[serde(default)]
+        when_full: WhenFull,
+    },
 }
 
 impl BufferType {
@@ -287,6 +322,15 @@ impl BufferType {
                         id.id(),
                     );
 
+                    Some(DiskUsage::new(id.clone(), data_dir, *max_size))
+                }
+                #[cfg(feature = "sqlite")]
+                Self::Sqlite { max_size, .. } => {
+                    let data_dir = crate::variants::sqlite::get_sqlite_data_dir_path(
+                        &global_data_dir,
+                        id.id(),
+                    );
+
                     Some(DiskUsage::new(id.clone(), data_dir, *max_size))
                 }
             },
@@ -319,6 +363,14 @@ impl BufferType {
                 let data_dir = data_dir.ok_or(BufferBuildError::RequiresDataDir)?;
                 builder.stage(DiskV2Buffer::new(id, data_dir, max_size), when_full);
             }
+            #[cfg(feature = "sqlite")]
+            BufferType::Sqlite {
+                when_full,
+                max_size,
+            } => {
+                let data_dir = data_dir.ok_or(BufferBuildError::RequiresDataDir)?;
+                builder.stage(SqliteBuffer::new(id, data_dir, max_size), when_full);
+            }
         }
 
         Ok(())
@@ -564,4 +616,23 @@ max_events: 42
             },
         );
     }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn parse_sqlite() {
+        check_single_stage(
+            r"
+          type: sqlite
+          max_size: 1024
+          when_full: drop_newest
+          ",
+            BufferType::Sqlite {
+                max_size: NonZeroU64::new(1024).unwrap(),
+                when_full: WhenFull::DropNewest,
+            },
+        );
+
+        let error = serde_yaml::from_str::<BufferConfig>("type: sqlite\nmax_events: 100\n");
+        assert!(error.is_err());
+    }
 }
@@ -11,6 +11,8 @@ use tokio_util::sync::ReusableBoxFuture;
 use vector_common::internal_event::emit;
 
 use super::limited_queue::LimitedReceiver;
+#[cfg(feature = "sqlite")]
+use crate::variants::sqlite;
 use crate::{
     Bufferable,
     buffer_usage_data::BufferUsageHandle,
@@ -26,6 +28,10 @@ pub enum ReceiverAdapter<T: Bufferable> {
 
     /// The disk v2 buffer.
     DiskV2(disk_v2::BufferReader<T, ProductionFilesystem>),
+
+    /// The SQLite buffer.
+    #[cfg(feature = "sqlite")]
+    Sqlite(sqlite::BufferReader<T>),
 }
 
 impl<T: Bufferable> From<LimitedReceiver<T>> for ReceiverAdapter<T> {
@@ -40,6 +46,13 @@ impl<T: Bufferable> From<disk_v2::BufferReader<T, ProductionFilesystem>> for Rec
     }
 }
 
+#[cfg(feature = "sqlite")]
+impl<T: Bufferable> From<sqlite::BufferReader<T>> for ReceiverAdapter<T> {
+    fn from(v: sqlite::BufferReader<T>) -> Self {
+        Self::Sqlite(v)
+    }
+}
+
 impl<T> ReceiverAdapter<T>
 where
     T: Bufferable,
@@ -60,6 +73,8 @@ where
                     },
                 }
             },
+            #[cfg(feature = "sqlite")]
+            ReceiverAdapter::Sqlite(reader) => reader.next().await,
         }
     }
 }
@@ -7,6 +7,8 @@ use tracing::Span;
 use vector_common::internal_event::{InternalEventHandle, Registered, register};
 
 use super::limited_queue::LimitedSender;
+#[cfg(feature = "sqlite")]
+use crate::variants::sqlite;
 use crate::{
     BufferInstrumentation, Bufferable, WhenFull,
     buffer_usage_data::BufferUsageHandle,
@@ -22,6 +24,10 @@ pub enum SenderAdapter<T: Bufferable> {
 
     /// The disk v2 buffer.
     DiskV2(Arc<Mutex<disk_v2::BufferWriter<T, ProductionFilesystem>>>),
+
+    /// The SQLite buffer.
+    #[cfg(feature = "sqlite")]
+    Sqlite(Arc<Mutex<sqlite::BufferWriter<T>>>),
 }
 
 impl<T: Bufferable> From<LimitedSender<T>> for SenderAdapter<T> {
@@ -36,6 +42,13 @@ impl<T: Bufferable> From<disk_v2::BufferWriter<T, ProductionFilesystem>> for Sen
     }
 }
 
+#[cfg(feature = "sqlite")]
+impl<T: Bufferable> From<sqlite::BufferWriter<T>> for SenderAdapter<T> {
+    fn from(v: sqlite::BufferWriter<T>) -> Self {
+        Self::Sqlite(Arc::new(Mutex::new(v)))
+    }
+}
+
 impl<T> SenderAdapter<T>
 where
     T: Bufferable,
@@ -57,6 +70,12 @@ where
                     e.into()
                 })
             }
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(writer) => writer.lock().await.write_record(item).await.map_err(|e| {
+                error!("SQLite buffer writer has encountered an unrecoverable error.");
+
+                e
+            }),
         }
     }
 
@@ -80,12 +99,26 @@ where
                     e.into()
                 })
             }
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(writer) => writer
+                .lock()
+                .await
+                .try_write_record(item)
+                .await
+                .map_err(|e| {
+                    error!("SQLite buffer writer has encountered an unrecoverable error.");
+
+                    e
+                }),
         }
     }
 
     pub(crate) async fn flush(&mut self) -> crate::Result<()> {
         match self {
             Self::InMemory(_) => Ok(()),
+            // Each record is committed when it is written.
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(_) => Ok(()),
             Self::DiskV2(writer) => {
                 let mut writer = writer.lock().await;
                 writer.flush().await.map_err(|e| {
@@ -102,6 +135,8 @@ where
         match self {
             Self::InMemory(tx) => Some(tx.available_capacity()),
             Self::DiskV2(_) => None,
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(_) => None,
         }
     }
 }
@@ -3,3 +3,8 @@ pub use disk_v2::DiskV2Buffer;
 
 pub(crate) mod in_memory;
 pub use in_memory::MemoryBuffer;
+
+#[cfg(feature = "sqlite")]
+pub(crate) mod sqlite;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteBuffer;
@@ -0,0 +1,447 @@
+//! # SQLite buffer.
+//!
+//! This buffer stores each record as a row of a single SQLite database file, so that the buffered
+//! data can be inspected, audited, or repaired with standard SQLite tooling while Vector is
+//! stopped.
+//!
+//! Records are written to the `records` table, with an increasing `id`, the encoding metadata of
+//! the record, its event count, and its encoded payload. The reader reads the records in `id`
+//! order, and a record is only deleted once all of its events have been acknowledged, so records
+//! that were read but not acknowledged are read again when the buffer is next opened.
+//!
+//! The size limit of the buffer applies to the total size of the payloads of its records. The
+//! writer waits for acknowledged records to be deleted when a write would exceed the limit.
+use std::{
+    error::Error,
+    marker::PhantomData,
+    num::NonZeroU64,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use bytes::BytesMut;
+use futures::StreamExt;
+use sqlx::{
+    Row, SqlitePool,
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous},
+};
+use tokio::sync::Notify;
+use vector_common::{
+    byte_size_of::ByteSizeOf, finalization::BatchNotifier, finalizer::OrderedFinalizer,
+};
+
+use crate::{
+    Bufferable,
+    buffer_usage_data::BufferUsageHandle,
+    encoding::{AsMetadata, Encodable},
+    topology::{
+        builder::IntoBuffer,
+        channel::{ReceiverAdapter, SenderAdapter},
+    },
+};
+
+/// The time to wait before retrying a read after the database failed.
+const READ_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+pub struct SqliteBuffer {
+    id: String,
+    data_dir: PathBuf,
+    max_size: NonZeroU64,
+}
+
+impl SqliteBuffer {
+    pub fn new(id: String, data_dir: PathBuf, max_size: NonZeroU64) -> Self {
+        Self {
+            id,
+            data_dir,
+            max_size,
+        }
+    }
+}
+
+#[async_trait]
+impl<T> IntoBuffer<T> for SqliteBuffer
+where
+    T: Bufferable,
+{
+    fn provides_instrumentation(&self) -> bool {
+        true
+    }
+
+    async fn into_buffer_parts(
+        self: Box<Self>,
+        usage_handle: BufferUsageHandle,
+    ) -> Result<(SenderAdapter<T>, ReceiverAdapter<T>), Box<dyn Error + Send + Sync>> {
+        let (writer, reader) = self.open(usage_handle).await?;
+        Ok((writer.into(), reader.into()))
+    }
+}
+
+impl SqliteBuffer {
+    async fn open<T: Bufferable>(
+        &self,
+        usage_handle: BufferUsageHandle,
+    ) -> Result<(BufferWriter<T>, BufferReader<T>), Box<dyn Error + Send + Sync>> {
+        usage_handle.set_buffer_limits(Some(self.max_size.get()), None);
+
+        let data_dir = get_sqlite_data_dir_path(&self.data_dir, &self.id);
+        tokio::fs::create_dir_all(&data_dir).await?;
+        let shared = Shared::open(&data_dir.join("buffer.db"), self.max_size, usage_handle).await?;
+
+        let writer = BufferWriter {
+            shared: Arc::clone(&shared),
+            _t: PhantomData,
+        };
+        let reader = BufferReader {
+            finalizer: Arc::clone(&shared).spawn_finalizer(),
+            last_id: 0,
+            shared,
+            _t: PhantomData,
+        };
+        Ok((writer, reader))
+    }
+}
+
+pub(crate) fn get_sqlite_data_dir_path(base_dir: &Path, buffer_id: &str) -> PathBuf {
+    base_dir.join("buffer").join("sqlite").join(buffer_id)
+}
+
+/// The state shared by the writer, the reader, and the task deleting acknowledged records.
+#[derive(Debug)]
+struct Shared {
+    pool: SqlitePool,
+    max_size: u64,
+    /// The total size of the payloads of the records in the database.
+    size: AtomicU64,
+    writer_done: AtomicBool,
+    /// Notified when a record is written, or when the writer is done.
+    reader_notify: Notify,
+    /// Notified when acknowledged records are deleted.
+    writer_notify: Notify,
+    usage_handle: BufferUsageHandle,
+}
+
+impl Shared {
+    async fn open(
+        path: &Path,
+        max_size: NonZeroU64,
+        usage_handle: BufferUsageHandle,
+    ) -> Result<Arc<Self>, sqlx::Error> {
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            .synchronous(SqliteSynchronous::Normal);
+        let pool = SqlitePoolOptions::new().connect_with(options).await?;
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS records (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                metadata INTEGER NOT NULL,
+                event_count INTEGER NOT NULL,
+                payload BLOB NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await?;
+
+        // Records left over from a previous run are counted as received, as they will be sent.
+        let row = sqlx::query(
+            "SELECT COALESCE(SUM(event_count), 0), COALESCE(SUM(LENGTH(payload)), 0) FROM records",
+        )
+        .fetch_one(&pool)
+        .await?;
+        let (event_count, size) = (row.get::<i64, _>(0) as u64, row.get::<i64, _>(1) as u64);
+        usage_handle.increment_received_event_count_and_byte_size(event_count, size);
+
+        Ok(Arc::new(Self {
+            pool,
+            max_size: max_size.get(),
+            size: AtomicU64::new(size),
+            writer_done: AtomicBool::new(false),
+            reader_notify: Notify::new(),
+            writer_notify: Notify::new(),
+            usage_handle,
+        }))
+    }
+
+    /// Whether a record of `len` bytes fits in the buffer.
+    ///
+    /// A record always fits in an empty buffer, so that records bigger than the limit do not
+    /// block the writer forever.
+    fn fits(&self, len: u64) -> bool {
+        let size = self.size.load(Ordering::Acquire);
+        size == 0 || size + len <= self.max_size
+    }
+
+    /// Spawns the task deleting records once their events are acknowledged.
+    fn spawn_finalizer(self: Arc<Self>) -> OrderedFinalizer<ReadRecord> {
+        let (finalizer, mut stream) = OrderedFinalizer::new(None);
+        tokio::spawn(async move {
+            while let Some((_status, record)) = stream.next().await {
+                if let Err(error) = sqlx::query("DELETE FROM records WHERE id = ?")
+                    .bind(record.id)
+                    .execute(&self.pool)
+                    .await
+                {
+                    error!(message = "Failed to delete acknowledged record.", %error);
+                }
+                self.size.fetch_sub(record.size, Ordering::AcqRel);
+                self.usage_handle
+                    .increment_sent_event_count_and_byte_size(record.event_count, record.size);
+                self.writer_notify.notify_one();
+            }
+        });
+        finalizer
+    }
+}
+
+/// A record that was read, identified for deletion once it is acknowledged.
+#[derive(Debug)]
+struct ReadRecord {
+    id: i64,
+    event_count: u64,
+    size: u64,
+}
+
+/// Writes records to a SQLite buffer.
+#[derive(Debug)]
+pub struct BufferWriter<T> {
+    shared: Arc<Shared>,
+    _t: PhantomData<T>,
+}
+
+impl<T: Bufferable> BufferWriter<T> {
+    /// Writes `item`, waiting until there is space in the buffer for it.
+    pub async fn write_record(&mut self, item: T) -> crate::Result<()> {
+        let event_count = item.event_count() as u64;
+        let payload = encode(item)?;
+
+        loop {
+            let notified = self.shared.writer_notify.notified();
+            if self.shared.fits(payload.len() as u64) {
+                break;
+            }
+            notified.await;
+        }
+
+        self.insert(event_count, payload).await
+    }
+
+    /// Writes `item` if there is space in the buffer for it, or returns it otherwise.
+    ///
+    /// Space is checked before encoding, so that a returned item keeps its finalizers. Items that
+    /// cannot tell their encoded size are estimated from their in-memory size.
+    pub async fn try_write_record(&mut self, item: T) -> crate::Result<Option<T>> {
+        let estimated_size = item
+            .encoded_size()
+            .unwrap_or_else(|| item.allocated_bytes());
+        if !self.shared.fits(estimated_size as u64) {
+            return Ok(Some(item));
+        }
+
+        let event_count = item.event_count() as u64;
+        let payload = encode(item)?;
+        self.insert(event_count, payload).await.map(|()| None)
+    }
+
+    async fn insert(&mut self, event_count: u64, payload: BytesMut) -> crate::Result<()> {
+        sqlx::query("INSERT INTO records (metadata, event_count, payload) VALUES (?, ?, ?)")
+            .bind(T::get_metadata().into_u32())
+            .bind(event_count as i64)
+            .bind(&payload[..])
+            .execute(&self.shared.pool)
+            .await?;
+
+        let size = payload.len() as u64;
+        self.shared.size.fetch_add(size, Ordering::AcqRel);
+        self.shared
+            .usage_handle
+            .increment_received_event_count_and_byte_size(event_count, size);
+        self.shared.reader_notify.notify_one();
+        Ok(())
+    }
+}
+
+fn encode<T: Bufferable>(item: T) -> crate::Result<BytesMut> {
+    let mut payload = BytesMut::new();
+    item.encode(&mut payload)?;
+    Ok(payload)
+}
+
+impl<T> Drop for BufferWriter<T> {
+    fn drop(&mut self) {
+        self.shared.writer_done.store(true, Ordering::Release);
+        self.shared.reader_notify.notify_one();
+    }
+}
+
+/// Reads records from a SQLite buffer.
+#[derive(Debug)]
+pub struct BufferReader<T> {
+    shared: Arc<Shared>,
+    /// The `id` of the last record that was read.
+    last_id: i64,
+    finalizer: OrderedFinalizer<ReadRecord>,
+    _t: PhantomData<T>,
+}
+
+impl<T: Bufferable> BufferReader<T> {
+    /// Reads the next record, waiting for one to be written if the buffer is empty.
+    ///
+    /// Returns `None` once the writer is done and every record has been read.
+    pub async fn next(&mut self) -> Option<T> {
+        loop {
+            let notified = self.shared.reader_notify.notified();
+            // Checked before reading, so that a record written right before the writer is done is
+            // still read.
+            let writer_done = self.shared.writer_done.load(Ordering::Acquire);
+
+            let row = sqlx::query(
+                "SELECT id, metadata, event_count, payload FROM records WHERE id > ? ORDER BY id \
+                 LIMIT 1",
+            )
+            .bind(self.last_id)
+            .fetch_optional(&self.shared.pool)
+            .await;
+            let row = match row {
+                Ok(Some(row)) => row,
+                Ok(None) if writer_done => return None,
+                Ok(None) => {
+                    notified.await;
+                    continue;
+                }
+                Err(error) => {
+                    error!(message = "Failed to read record from buffer.", %error);
+                    tokio::time::sleep(READ_RETRY_INTERVAL).await;
+                    continue;
+                }
+            };
+
+            let id = row.get::<i64, _>("id");
+            let event_count = row.get::<i64, _>("event_count") as u64;
+            let payload = row.get::<&[u8], _>("payload");
+            let size = payload.len() as u64;
+            self.last_id = id;
+
+            let decoded = T::Metadata::from_u32(row.get("metadata"))
+                .filter(|metadata| T::can_decode(*metadata))
+                .ok_or_else(|| "record has incompatible metadata".to_owned())
+                .and_then(|metadata| T::decode(metadata, payload).map_err(|e| e.to_string()));
+            let (batch, receiver) = BatchNotifier::new_with_receiver();
+            self.finalizer.add(
+                ReadRecord {
+                    id,
+                    event_count,
+                    size,
+                },
+                receiver,
+            );
+
+            match decoded {
+                Ok(mut record) => {
+                    record.add_batch_notifier(batch);
+                    return Some(record);
+                }
+                Err(error) => {
+                    // Dropping the notifier marks the record as acknowledged, deleting it.
+                    error!(message = "Failed to decode record from buffer, dropping it.", %error);
+                    self.shared
+                        .usage_handle
+                        .increment_dropped_event_count_and_byte_size(event_count, size, false);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_common::finalization::{AddBatchNotifier, Finalizable};
+
+    use super::*;
+    use crate::test::{SizedRecord, acknowledge, with_temp_dir};
+
+    async fn open(
+        data_dir: &Path,
+        max_size: u64,
+    ) -> (BufferWriter<SizedRecord>, BufferReader<SizedRecord>) {
+        let buffer = SqliteBuffer::new(
+            "sink".to_owned(),
+            data_dir.to_owned(),
+            NonZeroU64::new(max_size).unwrap(),
+        );
+        buffer.open(BufferUsageHandle::noop()).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn reads_records_in_order() {
+        with_temp_dir(|dir| {
+            let data_dir = dir.to_path_buf();
+            async move {
+                let (mut writer, mut reader) = open(&data_dir, 1024 * 1024).await;
+                for size in [12, 24, 36] {
+                    writer.write_record(SizedRecord::new(size)).await.unwrap();
+                }
+                drop(writer);
+
+                for size in [12, 24, 36] {
+                    let record = reader.next().await.unwrap();
+                    assert_eq!(record, SizedRecord::new(size));
+                    acknowledge(record).await;
+                }
+                assert_eq!(reader.next().await, None);
+            }
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn keeps_unacknowledged_records() {
+        with_temp_dir(|dir| {
+            let data_dir = dir.to_path_buf();
+            async move {
+                let (mut writer, mut reader) = open(&data_dir, 1024 * 1024).await;
+                writer.write_record(SizedRecord::new(12)).await.unwrap();
+                writer.write_record(SizedRecord::new(24)).await.unwrap();
+                drop(writer);
+
+                acknowledge(reader.next().await.unwrap()).await;
+                let _unacknowledged = reader.next().await.unwrap();
+                // The finalizer notifies the writer once it has deleted the acknowledged record.
+                reader.shared.writer_notify.notified().await;
+
+                let (writer, mut reader) = open(&data_dir, 1024 * 1024).await;
+                drop(writer);
+                assert_eq!(reader.next().await, Some(SizedRecord::new(24)));
+                assert_eq!(reader.next().await, None);
+            }
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn try_write_returns_records_that_do_not_fit() {
+        with_temp_dir(|dir| {
+            let data_dir = dir.to_path_buf();
+            async move {
+                // Each record takes 84 bytes: its length followed by 80 bytes of payload.
+                let (mut writer, _reader) = open(&data_dir, 100).await;
+                let record = SizedRecord::new(80);
+                assert_eq!(writer.try_write_record(record.clone()).await.unwrap(), None);
+
+                let (batch, _receiver) = BatchNotifier::new_with_receiver();
+                let mut second = record.clone();
+                second.add_batch_notifier(batch);
+                let mut returned = writer.try_write_record(second).await.unwrap().unwrap();
+                assert_eq!(returned, record);
+                assert!(!returned.take_finalizers().is_empty());
+            }
+        })
+        .await;
+    }
+}
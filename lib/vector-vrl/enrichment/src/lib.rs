@@ -2,6 +2,7 @@
 
 pub mod find_enrichment_table_records;
 pub mod get_enrichment_table_record;
+pub mod sql_lookup;
 pub mod tables;
 
 #[cfg(test)]
@@ -73,6 +74,8 @@ pub enum Error {
     OnlyOneFieldAllowed,
     #[snafu(display("Invalid address: {source}"))]
     InvalidAddress { source: std::net::AddrParseError },
+    #[snafu(display("Failed to query table: {details}"))]
+    QueryFailed { details: String },
     #[snafu(transparent)]
     Internal { source: InternalError },
     #[snafu(display("Table {table} not loaded"))]
@@ -149,6 +152,7 @@ pub fn vrl_functions() -> Vec<Box<dyn Function>> {
     vec![
         Box::new(get_enrichment_table_record::GetEnrichmentTableRecord) as _,
         Box::new(find_enrichment_table_records::FindEnrichmentTableRecords) as _,
+        Box::new(sql_lookup::SqlLookup) as _,
     ]
 }
 
@@ -0,0 +1,237 @@
+use std::sync::LazyLock;
+
+use vector_vrl_category::Category;
+use vrl::prelude::*;
+
+use crate::{
+    Case, Condition, IndexHandle, TableRegistry, TableSearch,
+    vrl_util::{self, DEFAULT_CASE_SENSITIVE, is_case_sensitive},
+};
+
+static PARAMETERS: LazyLock<Vec<Parameter>> = LazyLock::new(|| {
+    vec![
+        Parameter::required(
+            "table",
+            kind::BYTES,
+            "The [enrichment table](/docs/reference/glossary/#enrichment-tables) to search.",
+        ),
+        Parameter::required(
+            "key",
+            kind::BYTES,
+            "The column to look up the value in. Since the column is used at boot time to create an index into the table, it must be statically defined.",
+        ),
+        Parameter::required(
+            "value",
+            kind::BYTES | kind::INTEGER | kind::FLOAT | kind::BOOLEAN,
+            "The value of the `key` column of the row to return.",
+        ),
+        Parameter::optional(
+            "select",
+            kind::ARRAY,
+            "A subset of columns from the enrichment table to return. If not specified, all columns are returned.",
+        ),
+        Parameter::optional(
+            "case_sensitive",
+            kind::BOOLEAN,
+            "Whether text values match the case exactly.",
+        )
+        .default(&DEFAULT_CASE_SENSITIVE),
+    ]
+});
+
+#[derive(Clone, Copy, Debug)]
+pub struct SqlLookup;
+impl Function for SqlLookup {
+    fn identifier(&self) -> &'static str {
+        "sql_lookup"
+    }
+
+    fn usage(&self) -> &'static str {
+        "Looks up the row of an [enrichment table](/docs/reference/glossary/#enrichment-tables) whose `key` column equals the given value. It is meant for `database` and `sqlite` tables, but any enrichment table can be searched. A single row must be matched. If no rows are found or more than one row is found, an error is returned."
+    }
+
+    fn internal_failure_reasons(&self) -> &'static [&'static str] {
+        &[
+            "The row is not found.",
+            "Multiple rows are found that match the key.",
+            "The query against the database fails.",
+        ]
+    }
+
+    fn category(&self) -> &'static str {
+        Category::Enrichment.as_ref()
+    }
+
+    fn return_kind(&self) -> u16 {
+        kind::OBJECT
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &PARAMETERS
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            example! {
+                title: "Look up a row by key",
+                source: r#"sql_lookup!("test", "id", 1)"#,
+                result: Ok(r#"{"id": 1, "firstname": "Bob", "surname": "Smith"}"#),
+            },
+            example! {
+                title: "Case insensitive lookup",
+                source: r#"sql_lookup!("test", "surname", "smith", case_sensitive: false)"#,
+                result: Ok(r#"{"id": 1, "firstname": "Bob", "surname": "Smith"}"#),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        state: &TypeState,
+        ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let registry = ctx
+            .get_external_context_mut::<TableRegistry>()
+            .ok_or(Box::new(vrl_util::Error::TablesNotLoaded) as Box<dyn DiagnosticMessage>)?;
+
+        let tables = registry
+            .table_ids()
+            .into_iter()
+            .map(Value::from)
+            .collect::<Vec<_>>();
+
+        let table = arguments
+            .required_enum("table", &tables, state)?
+            .try_bytes_utf8_lossy()
+            .expect("table is not valid utf8")
+            .into_owned();
+        let key = arguments
+            .required_literal("key", state)?
+            .try_bytes_utf8_lossy()
+            .expect("key is not bytes")
+            .into_owned();
+        let value = arguments.required("value");
+        let select = arguments.optional("select");
+        let case_sensitive = is_case_sensitive(&arguments, state)?;
+
+        let index = registry
+            .add_index(&table, case_sensitive, &[&key])
+            .map_err(|err| Box::new(ExpressionError::from(err)) as Box<_>)?;
+
+        Ok(SqlLookupFn {
+            table,
+            key,
+            value,
+            index,
+            select,
+            case_sensitive,
+            enrichment_tables: registry.as_readonly(),
+        }
+        .as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SqlLookupFn {
+    table: String,
+    key: String,
+    value: Box<dyn Expression>,
+    index: IndexHandle,
+    select: Option<Box<dyn Expression>>,
+    case_sensitive: Case,
+    enrichment_tables: TableSearch,
+}
+
+impl FunctionExpression for SqlLookupFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let condition = [Condition::Equals {
+            field: &self.key,
+            value: self.value.resolve(ctx)?,
+        }];
+
+        let select = self
+            .select
+            .as_ref()
+            .map(|array| match array.resolve(ctx)? {
+                Value::Array(arr) => arr
+                    .iter()
+                    .map(|value| Ok(value.try_bytes_utf8_lossy()?.to_string()))
+                    .collect::<std::result::Result<Vec<_>, ExpressionError>>(),
+                value => Err(ValueError::Expected {
+                    got: value.kind(),
+                    expected: Kind::array(Collection::any()),
+                }
+                .into()),
+            })
+            .transpose()?;
+
+        let data = self.enrichment_tables.find_table_row(
+            &self.table,
+            self.case_sensitive,
+            &condition,
+            select.as_deref(),
+            None,
+            Some(self.index),
+        )?;
+
+        Ok(Value::Object(data))
+    }
+
+    fn type_def(&self, _: &TypeState) -> TypeDef {
+        TypeDef::object(Collection::any()).fallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        sync::{Arc, Mutex},
+    };
+
+    use vrl::{
+        compiler::{TargetValue, prelude::TimeZone, state::RuntimeState},
+        value,
+        value::Secrets,
+    };
+
+    use super::*;
+    use crate::test_util::{DummyEnrichmentTable, get_table_registry_with_tables};
+
+    #[test]
+    fn looks_up_row() {
+        let indexes = Arc::new(Mutex::new(Vec::new()));
+        let mut registry = get_table_registry_with_tables(vec![(
+            "users".to_string(),
+            DummyEnrichmentTable::new_with_index(indexes.clone()),
+        )]);
+        let index = registry
+            .add_index("users", Case::Sensitive, &["id"])
+            .unwrap();
+        let func = SqlLookupFn {
+            table: "users".to_string(),
+            key: "id".to_string(),
+            value: Box::new(expression::Literal::from(1)),
+            index,
+            select: None,
+            case_sensitive: Case::Sensitive,
+            enrichment_tables: registry.as_readonly(),
+        };
+
+        let tz = TimeZone::default();
+        let object: Value = BTreeMap::new().into();
+        let mut target = TargetValue {
+            value: object,
+            metadata: value!({}),
+            secrets: Secrets::new(),
+        };
+        let mut runtime_state = RuntimeState::default();
+        let mut ctx = Context::new(&mut target, &mut runtime_state, &tz);
+
+        registry.finish_load();
+
+        assert_eq!(Ok(value!({ "field": "result" })), func.resolve(&mut ctx));
+        assert_eq!(*indexes.lock().unwrap(), [vec!["id".to_string()]]);
+    }
+}
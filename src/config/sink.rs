@@ -117,6 +117,8 @@ where
             match stage {
                 BufferType::Memory { .. } => {}
                 BufferType::DiskV2 { .. } => resources.push(Resource::DiskBuffer(id.to_string())),
+                #[cfg(feature = "buffers-sqlite")]
+                BufferType::Sqlite { .. } => resources.push(Resource::DiskBuffer(id.to_string())),
             }
         }
         resources
@@ -0,0 +1,70 @@
+use metrics::{counter, gauge};
+use vector_lib::{
+    NamedInternalEvent,
+    internal_event::{InternalEvent, error_stage, error_type},
+};
+
+#[derive(Debug, NamedInternalEvent)]
+pub(crate) struct DatabaseEnrichmentTableRefreshed {
+    pub rows: usize,
+}
+
+impl InternalEvent for DatabaseEnrichmentTableRefreshed {
+    fn emit(self) {
+        debug!(
+            message = "Refreshed database enrichment table.",
+            rows = self.rows
+        );
+        counter!("database_enrichment_table_refreshes_total").increment(1);
+        gauge!("database_enrichment_table_rows").set(self.rows as f64);
+    }
+}
+
+#[derive(Debug, NamedInternalEvent)]
+pub(crate) struct DatabaseEnrichmentTableRefreshError<E> {
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for DatabaseEnrichmentTableRefreshError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to refresh database enrichment table; keeping the previous rows.",
+            error = %self.error,
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+        counter!("database_enrichment_table_failed_refreshes_total").increment(1);
+    }
+}
+
+#[derive(Debug, NamedInternalEvent)]
+pub(crate) struct DatabaseEnrichmentTableListenError<E> {
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for DatabaseEnrichmentTableListenError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to listen for database enrichment table notifications.",
+            error = %self.error,
+            error_type = error_type::CONNECTION_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+        counter!("database_enrichment_table_failed_listens_total").increment(1);
+    }
+}
+
+#[derive(Debug, NamedInternalEvent)]
+pub(crate) struct DatabaseEnrichmentTableRowsEvicted {
+    pub count: usize,
+}
+
+impl InternalEvent for DatabaseEnrichmentTableRowsEvicted {
+    fn emit(self) {
+        debug!(
+            message = "Evicted rows over the limits of database enrichment table.",
+            count = self.count
+        );
+        counter!("database_enrichment_table_evictions_total").increment(self.count as u64);
+    }
+}
@@ -0,0 +1,10 @@
+//! Handles enrichment tables for `type = database`.
+//! Enrichment data is loaded from the result of a query against a SQL database, and reloaded
+//! periodically.
+
+mod config;
+mod internal_events;
+mod table;
+
+pub use config::*;
+pub use table::*;
@@ -0,0 +1,598 @@
+use std::{
+    sync::{Arc, Mutex, Weak},
+    time::Duration,
+};
+
+use arc_swap::ArcSwap;
+use sqlx::{
+    Any, AnyPool, Column, Executor, Row, Statement, ValueRef,
+    any::{AnyArguments, AnyPoolOptions, AnyRow, AnyTypeInfoKind},
+    postgres::PgListener,
+    query::Query,
+};
+use tokio::time::{Instant, interval_at};
+use vector_lib::enrichment::{Case, Condition, Error, IndexHandle, Table};
+use vrl::value::{ObjectMap, Value};
+
+use super::{
+    DatabaseConfig, IncrementalRefreshConfig,
+    internal_events::{
+        DatabaseEnrichmentTableListenError, DatabaseEnrichmentTableRefreshError,
+        DatabaseEnrichmentTableRefreshed, DatabaseEnrichmentTableRowsEvicted,
+    },
+};
+use crate::enrichment_tables::dataset::Dataset;
+
+/// The state of a table, shared with the task that refreshes it.
+struct Shared {
+    pool: AnyPool,
+    query: String,
+    dataset: ArcSwap<Dataset>,
+    /// The indexes added to the table, in the order they were added. They are added again, in the
+    /// same order, to every refreshed dataset so that their handles stay valid.
+    indexes: Mutex<Vec<(Case, Vec<String>)>>,
+    incremental: Option<IncrementalRefreshConfig>,
+    /// The latest `updated_at` value of the rows, that incremental refreshes query changes after.
+    watermark: Mutex<Option<Value>>,
+    max_rows: Option<usize>,
+    max_bytes: Option<u64>,
+}
+
+impl Shared {
+    /// Refreshes the rows of the table, with the rows that changed if it is refreshed
+    /// incrementally, or else by running the query again.
+    async fn refresh(&self) -> crate::Result<usize> {
+        let watermark = self.watermark.lock().expect("mutex poisoned").clone();
+        match (&self.incremental, watermark) {
+            // Without any rows there is no watermark to query the changes after.
+            (Some(incremental), Some(watermark)) => {
+                self.merge_changes(incremental, &watermark).await
+            }
+            _ => self.reload().await,
+        }
+    }
+
+    /// Runs the query again and replaces the rows of the table with its result.
+    async fn reload(&self) -> crate::Result<usize> {
+        let (headers, data) = fetch_rows(&self.pool, &self.query, None).await?;
+        let mut dataset = Dataset::new(headers, data);
+        dataset.keep_usage(&self.dataset.load());
+        let indexes = self.indexes.lock().expect("mutex poisoned");
+        for (case, fields) in indexes.iter() {
+            let fields = fields.iter().map(String::as_str).collect::<Vec<_>>();
+            dataset.add_index(*case, &fields)?;
+        }
+        evict(&mut dataset, self.max_rows, self.max_bytes)?;
+        Ok(self.store(dataset))
+    }
+
+    /// Merges the rows that changed after `watermark` into the rows of the table.
+    async fn merge_changes(
+        &self,
+        incremental: &IncrementalRefreshConfig,
+        watermark: &Value,
+    ) -> crate::Result<usize> {
+        let (headers, data) = fetch_rows(&self.pool, &incremental.query, Some(watermark)).await?;
+        if data.is_empty() {
+            return Ok(self.dataset.load().len());
+        }
+
+        // Holding the lock keeps indexes from being added to the dataset while it is merged.
+        let _indexes = self.indexes.lock().expect("mutex poisoned");
+        let mut dataset = Dataset::clone(&self.dataset.load());
+        if headers.as_slice() != dataset.headers() {
+            return Err("The incremental query must return the same columns as `query`.".into());
+        }
+        dataset.upsert(&incremental.key, data)?;
+        evict(&mut dataset, self.max_rows, self.max_bytes)?;
+        Ok(self.store(dataset))
+    }
+
+    /// Replaces the rows of the table with `dataset`, returning the number of rows.
+    fn store(&self, dataset: Dataset) -> usize {
+        *self.watermark.lock().expect("mutex poisoned") =
+            latest_update(&dataset, self.incremental.as_ref());
+        let rows = dataset.len();
+        self.dataset.store(Arc::new(dataset));
+        rows
+    }
+}
+
+/// A struct that implements [vector_lib::enrichment::Table] to handle loading enrichment data
+/// from the result of a SQL query.
+#[derive(Clone)]
+pub struct Database {
+    shared: Arc<Shared>,
+}
+
+impl Database {
+    /// Runs the query of `config` to load the table, and starts refreshing it in the background
+    /// until the table is dropped.
+    pub async fn load(config: &DatabaseConfig) -> crate::Result<Self> {
+        let listener = match &config.notify_channel {
+            Some(channel) => Some(listen(&config.endpoint, channel).await?),
+            None => None,
+        };
+
+        sqlx::any::install_default_drivers();
+        let pool = AnyPoolOptions::new()
+            .max_connections(1)
+            .connect_lazy(&config.endpoint)?;
+        let (headers, data) = fetch_rows(&pool, &config.query, None).await?;
+        let mut dataset = Dataset::new(headers, data);
+        let mut indexes = Vec::new();
+        for fields in &config.indexes {
+            let fields = fields.iter().map(String::as_str).collect::<Vec<_>>();
+            add_index(&mut dataset, &mut indexes, Case::Sensitive, &fields)?;
+        }
+        evict(&mut dataset, config.max_rows, config.max_bytes)?;
+        emit!(DatabaseEnrichmentTableRefreshed {
+            rows: dataset.len()
+        });
+
+        let watermark = latest_update(&dataset, config.incremental.as_ref());
+        let shared = Arc::new(Shared {
+            pool,
+            query: config.query.clone(),
+            dataset: ArcSwap::from_pointee(dataset),
+            indexes: Mutex::new(indexes),
+            watermark: Mutex::new(watermark),
+            incremental: config.incremental.clone(),
+            max_rows: config.max_rows,
+            max_bytes: config.max_bytes,
+        });
+        let interval = Duration::from_secs(config.refresh_interval_secs.get());
+        tokio::spawn(run_refreshes(Arc::downgrade(&shared), interval, listener));
+        Ok(Self { shared })
+    }
+}
+
+/// Listens on the notification `channel` of the PostgreSQL database at `endpoint`.
+async fn listen(endpoint: &str, channel: &str) -> crate::Result<PgListener> {
+    if !(endpoint.starts_with("postgres://") || endpoint.starts_with("postgresql://")) {
+        return Err("`notify_channel` is only supported for PostgreSQL databases.".into());
+    }
+    let mut listener = PgListener::connect(endpoint).await?;
+    listener.listen(channel).await?;
+    Ok(listener)
+}
+
+/// Waits for the next notification on `listener`, or forever if there is none.
+///
+/// Also returns when the connection of the listener was lost, as notifications sent until it
+/// reconnects are missed.
+async fn notification(listener: &mut Option<PgListener>) -> Result<(), sqlx::Error> {
+    match listener {
+        Some(listener) => listener.try_recv().await.map(|_| ()),
+        None => std::future::pending().await,
+    }
+}
+
+async fn run_refreshes(shared: Weak<Shared>, period: Duration, mut listener: Option<PgListener>) {
+    let mut interval = interval_at(Instant::now() + period, period);
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            result = notification(&mut listener) => match result {
+                Ok(()) => interval.reset(),
+                Err(error) => {
+                    emit!(DatabaseEnrichmentTableListenError { error });
+                    // Wait for the scheduled refresh before trying to reconnect.
+                    interval.tick().await;
+                }
+            },
+        }
+        let Some(shared) = shared.upgrade() else {
+            break;
+        };
+        match shared.refresh().await {
+            Ok(rows) => emit!(DatabaseEnrichmentTableRefreshed { rows }),
+            Err(error) => emit!(DatabaseEnrichmentTableRefreshError { error }),
+        }
+    }
+}
+
+/// Adds the index of `fields` to `dataset`, recording it in `indexes` if it is new.
+fn add_index(
+    dataset: &mut Dataset,
+    indexes: &mut Vec<(Case, Vec<String>)>,
+    case: Case,
+    fields: &[&str],
+) -> Result<IndexHandle, Error> {
+    let handle = dataset.add_index(case, fields)?;
+    if handle.0 == indexes.len() {
+        indexes.push((case, fields.iter().map(|field| field.to_string()).collect()));
+    }
+    Ok(handle)
+}
+
+/// Evicts the least recently used rows of `dataset` that are over the limits, tracking the usage
+/// of its rows if there are any limits.
+fn evict(
+    dataset: &mut Dataset,
+    max_rows: Option<usize>,
+    max_bytes: Option<u64>,
+) -> Result<(), Error> {
+    if max_rows.is_none() && max_bytes.is_none() {
+        return Ok(());
+    }
+    dataset.track_usage();
+    let count = dataset.evict_least_recently_used(max_rows, max_bytes)?;
+    if count > 0 {
+        emit!(DatabaseEnrichmentTableRowsEvicted { count });
+    }
+    Ok(())
+}
+
+/// The latest value of the `updated_at` column of incremental refreshes in `dataset`.
+fn latest_update(
+    dataset: &Dataset,
+    incremental: Option<&IncrementalRefreshConfig>,
+) -> Option<Value> {
+    dataset
+        .column(&incremental?.updated_at)?
+        .fold(None, |latest, value| match latest {
+            Some(latest) if !is_later(value, latest) => Some(latest),
+            _ if value.is_null() => latest,
+            _ => Some(value),
+        })
+        .cloned()
+}
+
+fn is_later(value: &Value, other: &Value) -> bool {
+    match (value, other) {
+        (Value::Integer(value), Value::Integer(other)) => value > other,
+        (Value::Float(value), Value::Float(other)) => value > other,
+        (Value::Bytes(value), Value::Bytes(other)) => value > other,
+        (Value::Timestamp(value), Value::Timestamp(other)) => value > other,
+        _ => false,
+    }
+}
+
+fn bind_value<'q>(
+    query: Query<'q, Any, AnyArguments<'q>>,
+    value: &Value,
+) -> Query<'q, Any, AnyArguments<'q>> {
+    match value {
+        Value::Boolean(value) => query.bind(*value),
+        Value::Integer(value) => query.bind(*value),
+        Value::Float(value) => query.bind(value.into_inner()),
+        value => query.bind(value.to_string_lossy().into_owned()),
+    }
+}
+
+/// Runs the query `sql`, bound to `parameter` if there is one, and collects the headers and rows
+/// of its result.
+///
+/// The headers are the columns of the prepared statement, so that a query without any rows still
+/// has columns to index.
+async fn fetch_rows(
+    pool: &AnyPool,
+    sql: &str,
+    parameter: Option<&Value>,
+) -> Result<(Vec<String>, Vec<Vec<Value>>), sqlx::Error> {
+    let statement = pool.prepare(sql).await?;
+    let headers = statement
+        .columns()
+        .iter()
+        .map(|column| column.name().to_owned())
+        .collect();
+    let query = match parameter {
+        Some(parameter) => bind_value(statement.query(), parameter),
+        None => statement.query(),
+    };
+    let rows = query.fetch_all(pool).await?;
+    let data = rows
+        .iter()
+        .map(|row| (0..row.len()).map(|index| decode(row, index)).collect())
+        .collect::<Result<_, _>>()?;
+    Ok((headers, data))
+}
+
+fn decode(row: &AnyRow, index: usize) -> Result<Value, sqlx::Error> {
+    let kind = {
+        let value = row.try_get_raw(index)?;
+        if value.is_null() {
+            return Ok(Value::Null);
+        }
+        value.type_info().kind()
+    };
+    Ok(match kind {
+        AnyTypeInfoKind::Bool => row.try_get::<bool, _>(index)?.into(),
+        AnyTypeInfoKind::SmallInt => i64::from(row.try_get::<i16, _>(index)?).into(),
+        AnyTypeInfoKind::Integer => i64::from(row.try_get::<i32, _>(index)?).into(),
+        AnyTypeInfoKind::BigInt => row.try_get::<i64, _>(index)?.into(),
+        AnyTypeInfoKind::Real => Value::from_f64_or_zero(row.try_get::<f32, _>(index)?.into()),
+        AnyTypeInfoKind::Double => Value::from_f64_or_zero(row.try_get::<f64, _>(index)?),
+        AnyTypeInfoKind::Text => row.try_get::<String, _>(index)?.into(),
+        AnyTypeInfoKind::Blob => Value::Bytes(row.try_get::<Vec<u8>, _>(index)?.into()),
+        _ => Value::Null,
+    })
+}
+
+impl Table for Database {
+    fn find_table_row<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&'a [String]>,
+        wildcard: Option<&Value>,
+        index: Option<IndexHandle>,
+    ) -> Result<ObjectMap, Error> {
+        self.shared
+            .dataset
+            .load()
+            .find_table_row(case, condition, select, wildcard, index)
+    }
+
+    fn find_table_rows<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&'a [String]>,
+        wildcard: Option<&Value>,
+        index: Option<IndexHandle>,
+    ) -> Result<Vec<ObjectMap>, Error> {
+        self.shared
+            .dataset
+            .load()
+            .find_table_rows(case, condition, select, wildcard, index)
+    }
+
+    fn add_index(&mut self, case: Case, fields: &[&str]) -> Result<IndexHandle, Error> {
+        let mut indexes = self.shared.indexes.lock().expect("mutex poisoned");
+        let mut dataset = Dataset::clone(&self.shared.dataset.load());
+        let handle = add_index(&mut dataset, &mut indexes, case, fields)?;
+        self.shared.dataset.store(Arc::new(dataset));
+        Ok(handle)
+    }
+
+    /// Returns a list of the field names that are in each index
+    fn index_fields(&self) -> Vec<(Case, Vec<String>)> {
+        self.shared.dataset.load().index_fields()
+    }
+
+    /// The table is rebuilt on every configuration reload, so that changes to its configuration
+    /// are applied. The rebuilt table runs `query` again, and the refreshes of the previous table
+    /// stop once it is dropped.
+    fn needs_reload(&self) -> bool {
+        true
+    }
+}
+
+impl std::fmt::Debug for Database {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let dataset = self.shared.dataset.load();
+        write!(
+            f,
+            "Database {} row(s) {} index(es)",
+            dataset.len(),
+            dataset.index_count()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU64;
+
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    #[tokio::test]
+    async fn loads_and_refreshes_rows() {
+        let path = temp_dir().join("inventory.db");
+        let endpoint = format!("sqlite://{}?mode=rwc", path.display());
+        sqlx::any::install_default_drivers();
+        let pool = AnyPool::connect(&endpoint).await.unwrap();
+        sqlx::query("CREATE TABLE users (id INTEGER, name TEXT, score REAL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO users VALUES (1, 'alice', 0.5), (2, NULL, 1.5)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let config = DatabaseConfig {
+            endpoint,
+            query: "SELECT id, name, score FROM users".to_owned(),
+            refresh_interval_secs: NonZeroU64::new(3600).unwrap(),
+            notify_channel: None,
+            indexes: vec![vec!["name".to_owned()]],
+            incremental: None,
+            max_rows: None,
+            max_bytes: None,
+        };
+        let mut table = Database::load(&config).await.unwrap();
+        let index = table.add_index(Case::Sensitive, &["id"]).unwrap();
+        let condition = [Condition::Equals {
+            field: "id",
+            value: Value::from(2),
+        }];
+
+        assert_eq!(
+            Ok(ObjectMap::from([
+                ("id".into(), Value::from(2)),
+                ("name".into(), Value::Null),
+                ("score".into(), Value::from_f64_or_zero(1.5)),
+            ])),
+            table.find_table_row(Case::Sensitive, &condition, None, None, Some(index))
+        );
+
+        sqlx::query("UPDATE users SET name = 'bob' WHERE id = 2")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(table.shared.refresh().await.unwrap(), 2);
+
+        let row = table
+            .find_table_row(Case::Sensitive, &condition, None, None, Some(index))
+            .unwrap();
+        assert_eq!(row["name"], Value::from("bob"));
+        assert_eq!(
+            table.index_fields(),
+            [
+                (Case::Sensitive, vec!["name".to_owned()]),
+                (Case::Sensitive, vec!["id".to_owned()]),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn indexes_empty_results() {
+        let path = temp_dir().join("inventory.db");
+        let endpoint = format!("sqlite://{}?mode=rwc", path.display());
+        sqlx::any::install_default_drivers();
+        let pool = AnyPool::connect(&endpoint).await.unwrap();
+        sqlx::query("CREATE TABLE users (id INTEGER, name TEXT)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let config = DatabaseConfig {
+            endpoint,
+            query: "SELECT id, name FROM users".to_owned(),
+            refresh_interval_secs: NonZeroU64::new(3600).unwrap(),
+            notify_channel: None,
+            indexes: vec![vec!["name".to_owned()]],
+            incremental: None,
+            max_rows: None,
+            max_bytes: None,
+        };
+        let mut table = Database::load(&config).await.unwrap();
+        assert!(table.add_index(Case::Sensitive, &["id"]).is_ok());
+    }
+
+    #[tokio::test]
+    async fn notifications_require_postgres() {
+        let config = DatabaseConfig {
+            endpoint: "sqlite::memory:".to_owned(),
+            query: "SELECT 1".to_owned(),
+            refresh_interval_secs: NonZeroU64::new(3600).unwrap(),
+            notify_channel: Some("changes".to_owned()),
+            indexes: Vec::new(),
+            incremental: None,
+            max_rows: None,
+            max_bytes: None,
+        };
+        assert!(Database::load(&config).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn merges_changed_rows() {
+        let path = temp_dir().join("inventory.db");
+        let endpoint = format!("sqlite://{}?mode=rwc", path.display());
+        sqlx::any::install_default_drivers();
+        let pool = AnyPool::connect(&endpoint).await.unwrap();
+        sqlx::query("CREATE TABLE users (id INTEGER, name TEXT, updated_at INTEGER)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO users VALUES (1, 'alice', 1), (2, 'bob', 2)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let config = DatabaseConfig {
+            endpoint,
+            query: "SELECT id, name, updated_at FROM users".to_owned(),
+            refresh_interval_secs: NonZeroU64::new(3600).unwrap(),
+            notify_channel: None,
+            indexes: vec![vec!["name".to_owned()]],
+            incremental: Some(IncrementalRefreshConfig {
+                query: "SELECT id, name, updated_at FROM users WHERE updated_at > ?".to_owned(),
+                key: "id".to_owned(),
+                updated_at: "updated_at".to_owned(),
+            }),
+            max_rows: Some(2),
+            max_bytes: None,
+        };
+        let table = Database::load(&config).await.unwrap();
+
+        sqlx::query("UPDATE users SET name = 'robert', updated_at = 3 WHERE id = 2")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO users VALUES (3, 'carol', 4)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        // Alice was used least recently, so she is evicted to keep the table to two rows.
+        assert_eq!(table.shared.refresh().await.unwrap(), 2);
+        assert_eq!(
+            *table.shared.watermark.lock().unwrap(),
+            Some(Value::from(4))
+        );
+
+        let find = |name: &str| {
+            let condition = [Condition::Equals {
+                field: "name",
+                value: Value::from(name),
+            }];
+            table
+                .find_table_rows(
+                    Case::Sensitive,
+                    &condition,
+                    None,
+                    None,
+                    Some(IndexHandle(0)),
+                )
+                .unwrap()
+                .len()
+        };
+        assert_eq!(find("alice"), 0);
+        assert_eq!(find("bob"), 0);
+        assert_eq!(find("robert"), 1);
+        assert_eq!(find("carol"), 1);
+    }
+
+    #[tokio::test]
+    async fn evicts_least_recently_used_rows() {
+        let path = temp_dir().join("inventory.db");
+        let endpoint = format!("sqlite://{}?mode=rwc", path.display());
+        sqlx::any::install_default_drivers();
+        let pool = AnyPool::connect(&endpoint).await.unwrap();
+        sqlx::query("CREATE TABLE users (id INTEGER, name TEXT)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO users VALUES (1, 'alice'), (2, 'bob')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let config = DatabaseConfig {
+            endpoint,
+            query: "SELECT id, name FROM users ORDER BY id".to_owned(),
+            refresh_interval_secs: NonZeroU64::new(3600).unwrap(),
+            notify_channel: None,
+            indexes: Vec::new(),
+            incremental: None,
+            max_rows: Some(2),
+            max_bytes: None,
+        };
+        let table = Database::load(&config).await.unwrap();
+        let find = |id: i64| {
+            let condition = [Condition::Equals {
+                field: "id",
+                value: Value::from(id),
+            }];
+            table
+                .find_table_rows(Case::Sensitive, &condition, None, None, None)
+                .unwrap()
+                .len()
+        };
+        assert_eq!(find(1), 1);
+
+        sqlx::query("INSERT INTO users VALUES (3, 'carol')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        // Bob is the first row that has not been used since alice was looked up.
+        assert_eq!(table.shared.refresh().await.unwrap(), 2);
+        assert_eq!(find(1), 1);
+        assert_eq!(find(2), 0);
+        assert_eq!(find(3), 1);
+    }
+}
@@ -0,0 +1,635 @@
+//! The rows of an enrichment table held in memory, searched sequentially or through hash
+//! indexes over some of their columns.
+use std::{
+    collections::HashMap,
+    hash::Hasher,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use indexmap::IndexMap;
+use vector_lib::{
+    ByteSizeOf,
+    enrichment::{Case, Condition, Error, IndexHandle},
+};
+use vrl::value::{ObjectMap, Value};
+
+/// Rows of values for a fixed set of columns, with the indexes that were added to them.
+#[derive(Clone, Default)]
+pub struct Dataset {
+    headers: Vec<String>,
+    data: Vec<Vec<Value>>,
+    /// When each row was last used, if usage is tracked for evicting rows.
+    usage: Option<Usage>,
+    indexes: Vec<(
+        Case,
+        Vec<usize>,
+        HashMap<u64, Vec<usize>, hash_hasher::HashBuildHasher>,
+    )>,
+}
+
+/// When each row of a dataset was last used by a lookup.
+#[derive(Default)]
+struct Usage {
+    /// When each row was last used, as a tick of `clock`, or `0` if it has not been used since
+    /// usage started being tracked.
+    used: Vec<AtomicU64>,
+    clock: AtomicU64,
+}
+
+impl Clone for Usage {
+    fn clone(&self) -> Self {
+        Self {
+            used: self
+                .used
+                .iter()
+                .map(|used| AtomicU64::new(used.load(Ordering::Relaxed)))
+                .collect(),
+            clock: AtomicU64::new(self.clock.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+impl Usage {
+    fn new(rows: usize) -> Self {
+        Self {
+            used: (0..rows).map(|_| AtomicU64::default()).collect(),
+            clock: AtomicU64::default(),
+        }
+    }
+
+    fn tick(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    fn mark_used(&self, row: usize) {
+        self.used[row].store(self.tick(), Ordering::Relaxed);
+    }
+
+    fn last_used(&self, row: usize) -> u64 {
+        self.used[row].load(Ordering::Relaxed)
+    }
+}
+
+impl Dataset {
+    /// Creates a dataset of `data`, whose rows hold a value for each of the `headers`.
+    pub const fn new(headers: Vec<String>, data: Vec<Vec<Value>>) -> Self {
+        Self {
+            headers,
+            data,
+            usage: None,
+            indexes: Vec::new(),
+        }
+    }
+
+    /// Starts tracking when each row is used by a lookup, for evicting the least recently used
+    /// rows. Tracking has a cost on every lookup, so it is only started for limited tables.
+    pub fn track_usage(&mut self) {
+        if self.usage.is_none() {
+            self.usage = Some(Usage::new(self.data.len()));
+        }
+    }
+
+    pub const fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub const fn index_count(&self) -> usize {
+        self.indexes.len()
+    }
+
+    pub fn headers(&self) -> &[String] {
+        &self.headers
+    }
+
+    /// The values of `column` in each row, if it is a column of the dataset.
+    pub fn column(&self, column: &str) -> Option<impl Iterator<Item = &Value>> {
+        let idx = self.column_index(column)?;
+        Some(self.data.iter().map(move |row| &row[idx]))
+    }
+
+    /// Replaces the rows with the same value of the `key` column as one of `rows`, and adds the
+    /// others, then rebuilds the indexes.
+    ///
+    /// When usage is tracked, the replaced and added rows count as used, so they are evicted
+    /// after the rows that have not been used since. The `rows` must hold a value for each of
+    /// the headers.
+    pub fn upsert(&mut self, key: &str, rows: Vec<Vec<Value>>) -> Result<(), Error> {
+        let key = self.normalize_index_fields(&[key])?[0];
+        let encode = |row: &[Value]| {
+            row[key]
+                .encode_as_bytes()
+                .map_err(|details| Error::FailedToEncodeValue { details })
+        };
+
+        let mut changed = IndexMap::with_capacity(rows.len());
+        for row in rows {
+            changed.insert(encode(&row)?, row);
+        }
+        let kept = self
+            .data
+            .iter()
+            .map(|row| Ok(!changed.contains_key(&encode(row)?)))
+            .collect::<Result<Vec<_>, Error>>()?;
+        self.retain_rows(&kept);
+        for row in changed.into_values() {
+            self.data.push(row);
+            if let Some(usage) = &mut self.usage {
+                let used = usage.tick();
+                usage.used.push(AtomicU64::new(used));
+            }
+        }
+        self.rebuild_indexes()
+    }
+
+    /// Carries over when each row was last used from `previous`, if it tracks usage, for the
+    /// rows that are also in `previous` with the same values.
+    pub fn keep_usage(&mut self, previous: &Self) {
+        let Some(previous_usage) = &previous.usage else {
+            return;
+        };
+        self.track_usage();
+        let usage = self.usage.as_ref().expect("usage is tracked");
+        usage.clock.store(
+            previous_usage.clock.load(Ordering::Relaxed),
+            Ordering::Relaxed,
+        );
+        if self.headers != previous.headers {
+            return;
+        }
+        let used = previous
+            .data
+            .iter()
+            .enumerate()
+            .filter_map(|(index, row)| Some((hash_row(row)?, previous_usage.last_used(index))))
+            .collect::<HashMap<_, _>>();
+        for (row, row_used) in self.data.iter().zip(&usage.used) {
+            if let Some(used) = hash_row(row).and_then(|hash| used.get(&hash)) {
+                row_used.store(*used, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Removes the least recently used rows until there are at most `max_rows` rows taking up at
+    /// most `max_bytes` bytes, then rebuilds the indexes. Returns the number of rows removed.
+    ///
+    /// A row is used when a lookup returns it while usage is tracked. Rows that have been used
+    /// equally recently are removed in order, starting from the first row.
+    pub fn evict_least_recently_used(
+        &mut self,
+        max_rows: Option<usize>,
+        max_bytes: Option<u64>,
+    ) -> Result<usize, Error> {
+        let row_size = |row: &Vec<Value>| row.iter().map(ByteSizeOf::size_of).sum::<usize>() as u64;
+        let mut byte_size = self.data.iter().map(row_size).sum::<u64>();
+        let mut rows = self.data.len();
+
+        let mut order = (0..rows).collect::<Vec<_>>();
+        if let Some(usage) = &self.usage {
+            order.sort_by_key(|index| usage.last_used(*index));
+        }
+        let mut kept = vec![true; rows];
+        for index in order {
+            if max_rows.is_none_or(|max| rows <= max)
+                && max_bytes.is_none_or(|max| byte_size <= max)
+            {
+                break;
+            }
+            byte_size -= row_size(&self.data[index]);
+            rows -= 1;
+            kept[index] = false;
+        }
+
+        let count = self.data.len() - rows;
+        if count > 0 {
+            self.retain_rows(&kept);
+            self.rebuild_indexes()?;
+        }
+        Ok(count)
+    }
+
+    /// Keeps the rows, and when they were last used, for which `kept` is `true`.
+    fn retain_rows(&mut self, kept: &[bool]) {
+        let mut row_kept = kept.iter();
+        self.data
+            .retain(|_| *row_kept.next().expect("a flag per row"));
+        if let Some(usage) = &mut self.usage {
+            let mut row_kept = kept.iter();
+            usage
+                .used
+                .retain(|_| *row_kept.next().expect("a flag per row"));
+        }
+    }
+
+    fn rebuild_indexes(&mut self) -> Result<(), Error> {
+        let indexes = self
+            .indexes
+            .iter()
+            .map(|(case, fields, _)| Ok((*case, fields.clone(), self.index_data(fields, *case)?)))
+            .collect::<Result<_, Error>>()?;
+        self.indexes = indexes;
+        Ok(())
+    }
+
+    fn column_index(&self, col: &str) -> Option<usize> {
+        self.headers.iter().position(|header| header == col)
+    }
+
+    /// Does the given row match all the conditions specified?
+    fn row_equals(
+        &self,
+        case: Case,
+        condition: &[Condition],
+        row: &[Value],
+        wildcard: Option<&Value>,
+    ) -> bool {
+        condition.iter().all(|condition| match condition {
+            Condition::Equals { field, value } => match self.column_index(field) {
+                None => false,
+                Some(idx) => {
+                    let current_row_value = &row[idx];
+
+                    // Helper closure for comparing current_row_value with another value,
+                    // respecting the specified case for Value::Bytes.
+                    let compare_values = |val_to_compare: &Value| -> bool {
+                        match (case, current_row_value, val_to_compare) {
+                            (
+                                Case::Insensitive,
+                                Value::Bytes(bytes_row),
+                                Value::Bytes(bytes_cmp),
+                            ) => {
+                                // Perform case-insensitive comparison for byte strings.
+                                // If both are valid UTF-8, compare their lowercase versions.
+                                // If both are non-UTF-8 bytes, compare them directly.
+                                // If one is UTF-8 and the other is not, they are considered not equal.
+                                match (
+                                    std::str::from_utf8(bytes_row),
+                                    std::str::from_utf8(bytes_cmp),
+                                ) {
+                                    (Ok(s_row), Ok(s_cmp)) => {
+                                        s_row.to_lowercase() == s_cmp.to_lowercase()
+                                    }
+                                    (Err(_), Err(_)) => bytes_row == bytes_cmp,
+                                    _ => false,
+                                }
+                            }
+                            // For Case::Sensitive, or for Case::Insensitive with non-Bytes types,
+                            // perform a direct equality check.
+                            _ => current_row_value == val_to_compare,
+                        }
+                    };
+
+                    // First, check if the row value matches the condition's value.
+                    if compare_values(value) {
+                        true
+                    } else if let Some(wc_val) = wildcard {
+                        // If not, and a wildcard is provided, check if the row value matches the wildcard.
+                        compare_values(wc_val)
+                    } else {
+                        // Otherwise, no match.
+                        false
+                    }
+                }
+            },
+            Condition::BetweenDates { field, from, to } => match self.column_index(field) {
+                None => false,
+                Some(idx) => match row[idx] {
+                    Value::Timestamp(date) => from <= &date && &date <= to,
+                    _ => false,
+                },
+            },
+            Condition::FromDate { field, from } => match self.column_index(field) {
+                None => false,
+                Some(idx) => match row[idx] {
+                    Value::Timestamp(date) => from <= &date,
+                    _ => false,
+                },
+            },
+            Condition::ToDate { field, to } => match self.column_index(field) {
+                None => false,
+                Some(idx) => match row[idx] {
+                    Value::Timestamp(date) => &date <= to,
+                    _ => false,
+                },
+            },
+        })
+    }
+
+    fn add_columns(&self, select: Option<&[String]>, row: &[Value]) -> ObjectMap {
+        self.headers
+            .iter()
+            .zip(row)
+            .filter(|(header, _)| {
+                select
+                    .map(|select| select.contains(header))
+                    // If no select is passed, we assume all columns are included
+                    .unwrap_or(true)
+            })
+            .map(|(header, col)| (header.as_str().into(), col.clone()))
+            .collect()
+    }
+
+    /// Order the fields in the index according to the position they are found in the header.
+    fn normalize_index_fields(&self, index: &[&str]) -> Result<Vec<usize>, Error> {
+        // Get the positions of the fields we are indexing
+        let normalized = self
+            .headers
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, col)| {
+                if index.contains(&col.as_ref()) {
+                    Some(idx)
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if normalized.len() != index.len() {
+            let fields = index
+                .iter()
+                .filter_map(|col| {
+                    if self.headers.iter().any(|header| header == *col) {
+                        None
+                    } else {
+                        Some(col.to_string())
+                    }
+                })
+                .collect();
+            Err(Error::MissingDatasetFields { fields })
+        } else {
+            Ok(normalized)
+        }
+    }
+
+    /// Creates an index with the given fields.
+    /// Uses seahash to create a hash of the data that is used as the key in a hashmap lookup to
+    /// the index of the row in the data.
+    ///
+    /// Ensure fields that are searched via a comparison are not included in the index!
+    fn index_data(
+        &self,
+        fieldidx: &[usize],
+        case: Case,
+    ) -> Result<HashMap<u64, Vec<usize>, hash_hasher::HashBuildHasher>, Error> {
+        let mut index = HashMap::with_capacity_and_hasher(
+            self.data.len(),
+            hash_hasher::HashBuildHasher::default(),
+        );
+
+        for (idx, row) in self.data.iter().enumerate() {
+            let mut hash = seahash::SeaHasher::default();
+
+            for idx in fieldidx {
+                hash_value(&mut hash, case, &row[*idx])?;
+            }
+
+            let key = hash.finish();
+
+            let entry = index.entry(key).or_insert_with(Vec::new);
+            entry.push(idx);
+        }
+
+        index.shrink_to_fit();
+
+        Ok(index)
+    }
+
+    /// Sequentially searches through the rows at the positions of the iterator for the given
+    /// condition, marking the rows that match as used if usage is tracked.
+    fn sequential<'a, I>(
+        &'a self,
+        rows: I,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&'a [String]>,
+        wildcard: Option<&'a Value>,
+    ) -> impl Iterator<Item = ObjectMap> + 'a
+    where
+        I: Iterator<Item = usize> + 'a,
+    {
+        rows.filter_map(move |idx| {
+            let row = &self.data[idx];
+            if self.row_equals(case, condition, row, wildcard) {
+                if let Some(usage) = &self.usage {
+                    usage.mark_used(idx);
+                }
+                Some(self.add_columns(select, row))
+            } else {
+                None
+            }
+        })
+    }
+
+    fn indexed<'a>(
+        &'a self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        handle: IndexHandle,
+    ) -> Result<Option<&'a Vec<usize>>, Error> {
+        // The index to use has been passed, we can use this to search the data.
+        // We are assuming that the caller has passed an index that represents the fields
+        // being passed in the condition.
+        let mut hash = seahash::SeaHasher::default();
+
+        for header in self.headers.iter() {
+            if let Some(Condition::Equals { value, .. }) = condition.iter().find(
+                |condition| matches!(condition, Condition::Equals { field, .. } if field == header),
+            ) {
+                hash_value(&mut hash, case, value)?;
+            }
+        }
+
+        let key = hash.finish();
+
+        let IndexHandle(handle) = handle;
+        Ok(self.indexes[handle].2.get(&key))
+    }
+
+    fn indexed_with_wildcard<'a>(
+        &'a self,
+        case: Case,
+        wildcard: &'a Value,
+        condition: &'a [Condition<'a>],
+        handle: IndexHandle,
+    ) -> Result<Option<&'a Vec<usize>>, Error> {
+        if let Some(result) = self.indexed(case, condition, handle)? {
+            return Ok(Some(result));
+        }
+
+        // If lookup fails and a wildcard is provided, compute hash for the wildcard
+        let mut wildcard_hash = seahash::SeaHasher::default();
+        for header in self.headers.iter() {
+            if condition.iter().any(
+                |condition| matches!(condition, Condition::Equals { field, .. } if field == header),
+            ) {
+                hash_value(&mut wildcard_hash, case, wildcard)?;
+            }
+        }
+
+        let wildcard_key = wildcard_hash.finish();
+        let IndexHandle(handle) = handle;
+        Ok(self.indexes[handle].2.get(&wildcard_key))
+    }
+
+    pub fn find_table_row<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&'a [String]>,
+        wildcard: Option<&Value>,
+        index: Option<IndexHandle>,
+    ) -> Result<ObjectMap, Error> {
+        match index {
+            None => {
+                // No index has been passed so we need to do a Sequential Scan.
+                single_or_err(self.sequential(
+                    0..self.data.len(),
+                    case,
+                    condition,
+                    select,
+                    wildcard,
+                ))
+            }
+            Some(handle) => {
+                let result = if let Some(wildcard) = wildcard {
+                    self.indexed_with_wildcard(case, wildcard, condition, handle)?
+                } else {
+                    self.indexed(case, condition, handle)?
+                }
+                .ok_or(Error::NoRowsFound)?
+                .iter()
+                .copied();
+
+                // Perform a sequential scan over the indexed result.
+                single_or_err(self.sequential(result, case, condition, select, wildcard))
+            }
+        }
+    }
+
+    pub fn find_table_rows<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&'a [String]>,
+        wildcard: Option<&Value>,
+        index: Option<IndexHandle>,
+    ) -> Result<Vec<ObjectMap>, Error> {
+        match index {
+            None => {
+                // No index has been passed so we need to do a Sequential Scan.
+                Ok(self
+                    .sequential(0..self.data.len(), case, condition, select, wildcard)
+                    .collect())
+            }
+            Some(handle) => {
+                // Perform a sequential scan over the indexed result.
+                let indexed_result = if let Some(wildcard) = wildcard {
+                    self.indexed_with_wildcard(case, wildcard, condition, handle)?
+                } else {
+                    self.indexed(case, condition, handle)?
+                };
+
+                Ok(self
+                    .sequential(
+                        indexed_result.into_iter().flatten().copied(),
+                        case,
+                        condition,
+                        select,
+                        wildcard,
+                    )
+                    .collect())
+            }
+        }
+    }
+
+    pub fn add_index(&mut self, case: Case, fields: &[&str]) -> Result<IndexHandle, Error> {
+        let normalized = self.normalize_index_fields(fields)?;
+        match self
+            .indexes
+            .iter()
+            .position(|index| index.0 == case && index.1 == normalized)
+        {
+            Some(pos) => {
+                // This index already exists
+                Ok(IndexHandle(pos))
+            }
+            None => {
+                let index = self.index_data(&normalized, case)?;
+                self.indexes.push((case, normalized, index));
+                // The returned index handle is the position of the index in our list of indexes.
+                Ok(IndexHandle(self.indexes.len() - 1))
+            }
+        }
+    }
+
+    /// Returns a list of the field names that are in each index
+    pub fn index_fields(&self) -> Vec<(Case, Vec<String>)> {
+        self.indexes
+            .iter()
+            .map(|index| {
+                let (case, fields, _) = index;
+                (
+                    *case,
+                    fields
+                        .iter()
+                        .map(|idx| self.headers[*idx].clone())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>()
+    }
+}
+
+/// Adds the bytes from the given value to the hash.
+/// Each field is terminated by a `0` value to separate the fields
+fn hash_value(hasher: &mut seahash::SeaHasher, case: Case, value: &Value) -> Result<(), Error> {
+    match value {
+        Value::Bytes(bytes) => match case {
+            Case::Sensitive => hasher.write(bytes),
+            Case::Insensitive => hasher.write(
+                std::str::from_utf8(bytes)
+                    .map_err(|source| Error::InvalidUtfInColumn { source })?
+                    .to_lowercase()
+                    .as_bytes(),
+            ),
+        },
+        value => {
+            let bytes: bytes::Bytes = value
+                .encode_as_bytes()
+                .map_err(|details| Error::FailedToEncodeValue { details })?;
+            hasher.write(&bytes);
+        }
+    }
+
+    hasher.write_u8(0);
+
+    Ok(())
+}
+
+/// Hashes all values of `row`, or returns `None` if one of them cannot be hashed.
+fn hash_row(row: &[Value]) -> Option<u64> {
+    let mut hash = seahash::SeaHasher::default();
+    for value in row {
+        hash_value(&mut hash, Case::Sensitive, value).ok()?;
+    }
+    Some(hash.finish())
+}
+
+/// Returns an error if the iterator doesn't yield exactly one result.
+fn single_or_err<I, T>(mut iter: T) -> Result<I, Error>
+where
+    T: Iterator<Item = I>,
+{
+    let result = iter.next();
+
+    if iter.next().is_some() {
+        // More than one row has been found.
+        Err(Error::MoreThanOneRowFound)
+    } else {
+        result.ok_or(Error::NoRowsFound)
+    }
+}
@@ -1,5 +1,5 @@
 //! Handles enrichment tables for `type = file`.
-use std::{collections::HashMap, fs, hash::Hasher, path::PathBuf, time::SystemTime};
+use std::{collections::HashMap, fs, path::PathBuf, time::SystemTime};
 
 use bytes::Bytes;
 use tracing::trace;
@@ -11,6 +11,7 @@ use vector_lib::{
 };
 use vrl::value::{ObjectMap, Value};
 
+use super::dataset::Dataset;
 use crate::config::EnrichmentTableConfig;
 
 /// File encoding configuration.
@@ -264,13 +265,7 @@ pub struct FileData {
 pub struct File {
     config: FileConfig,
     last_modified: SystemTime,
-    data: Vec<Vec<Value>>,
-    headers: Vec<String>,
-    indexes: Vec<(
-        Case,
-        Vec<usize>,
-        HashMap<u64, Vec<usize>, hash_hasher::HashBuildHasher>,
-    )>,
+    dataset: Dataset,
 }
 
 impl File {
@@ -279,288 +274,9 @@ impl File {
         Self {
             config,
             last_modified: data.modified,
-            data: data.data,
-            headers: data.headers,
-            indexes: Vec::new(),
+            dataset: Dataset::new(data.headers, data.data),
         }
     }
-
-    fn column_index(&self, col: &str) -> Option<usize> {
-        self.headers.iter().position(|header| header == col)
-    }
-
-    /// Does the given row match all the conditions specified?
-    fn row_equals(
-        &self,
-        case: Case,
-        condition: &[Condition],
-        row: &[Value],
-        wildcard: Option<&Value>,
-    ) -> bool {
-        condition.iter().all(|condition| match condition {
-            Condition::Equals { field, value } => match self.column_index(field) {
-                None => false,
-                Some(idx) => {
-                    let current_row_value = &row[idx];
-
-                    // Helper closure for comparing current_row_value with another value,
-                    // respecting the specified case for Value::Bytes.
-                    let compare_values = |val_to_compare: &Value| -> bool {
-                        match (case, current_row_value, val_to_compare) {
-                            (
-                                Case::Insensitive,
-                                Value::Bytes(bytes_row),
-                                Value::Bytes(bytes_cmp),
-                            ) => {
-                                // Perform case-insensitive comparison for byte strings.
-                                // If both are valid UTF-8, compare their lowercase versions.
-                                // If both are non-UTF-8 bytes, compare them directly.
-                                // If one is UTF-8 and the other is not, they are considered not equal.
-                                match (
-                                    std::str::from_utf8(bytes_row),
-                                    std::str::from_utf8(bytes_cmp),
-                                ) {
-                                    (Ok(s_row), Ok(s_cmp)) => {
-                                        s_row.to_lowercase() == s_cmp.to_lowercase()
-                                    }
-                                    (Err(_), Err(_)) => bytes_row == bytes_cmp,
-                                    _ => false,
-                                }
-                            }
-                            // For Case::Sensitive, or for Case::Insensitive with non-Bytes types,
-                            // perform a direct equality check.
-                            _ => current_row_value == val_to_compare,
-                        }
-                    };
-
-                    // First, check if the row value matches the condition's value.
-                    if compare_values(value) {
-                        true
-                    } else if let Some(wc_val) = wildcard {
-                        // If not, and a wildcard is provided, check if the row value matches the wildcard.
-                        compare_values(wc_val)
-                    } else {
-                        // Otherwise, no match.
-                        false
-                    }
-                }
-            },
-            Condition::BetweenDates { field, from, to } => match self.column_index(field) {
-                None => false,
-                Some(idx) => match row[idx] {
-                    Value::Timestamp(date) => from <= &date && &date <= to,
-                    _ => false,
-                },
-            },
-            Condition::FromDate { field, from } => match self.column_index(field) {
-                None => false,
-                Some(idx) => match row[idx] {
-                    Value::Timestamp(date) => from <= &date,
-                    _ => false,
-                },
-            },
-            Condition::ToDate { field, to } => match self.column_index(field) {
-                None => false,
-                Some(idx) => match row[idx] {
-                    Value::Timestamp(date) => &date <= to,
-                    _ => false,
-                },
-            },
-        })
-    }
-
-    fn add_columns(&self, select: Option<&[String]>, row: &[Value]) -> ObjectMap {
-        self.headers
-            .iter()
-            .zip(row)
-            .filter(|(header, _)| {
-                select
-                    .map(|select| select.contains(header))
-                    // If no select is passed, we assume all columns are included
-                    .unwrap_or(true)
-            })
-            .map(|(header, col)| (header.as_str().into(), col.clone()))
-            .collect()
-    }
-
-    /// Order the fields in the index according to the position they are found in the header.
-    fn normalize_index_fields(&self, index: &[&str]) -> Result<Vec<usize>, Error> {
-        // Get the positions of the fields we are indexing
-        let normalized = self
-            .headers
-            .iter()
-            .enumerate()
-            .filter_map(|(idx, col)| {
-                if index.contains(&col.as_ref()) {
-                    Some(idx)
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<_>>();
-
-        if normalized.len() != index.len() {
-            let fields = index
-                .iter()
-                .filter_map(|col| {
-                    if self.headers.iter().any(|header| header == *col) {
-                        None
-                    } else {
-                        Some(col.to_string())
-                    }
-                })
-                .collect();
-            Err(Error::MissingDatasetFields { fields })
-        } else {
-            Ok(normalized)
-        }
-    }
-
-    /// Creates an index with the given fields.
-    /// Uses seahash to create a hash of the data that is used as the key in a hashmap lookup to
-    /// the index of the row in the data.
-    ///
-    /// Ensure fields that are searched via a comparison are not included in the index!
-    fn index_data(
-        &self,
-        fieldidx: &[usize],
-        case: Case,
-    ) -> Result<HashMap<u64, Vec<usize>, hash_hasher::HashBuildHasher>, Error> {
-        let mut index = HashMap::with_capacity_and_hasher(
-            self.data.len(),
-            hash_hasher::HashBuildHasher::default(),
-        );
-
-        for (idx, row) in self.data.iter().enumerate() {
-            let mut hash = seahash::SeaHasher::default();
-
-            for idx in fieldidx {
-                hash_value(&mut hash, case, &row[*idx])?;
-            }
-
-            let key = hash.finish();
-
-            let entry = index.entry(key).or_insert_with(Vec::new);
-            entry.push(idx);
-        }
-
-        index.shrink_to_fit();
-
-        Ok(index)
-    }
-
-    /// Sequentially searches through the iterator for the given condition.
-    fn sequential<'a, I>(
-        &'a self,
-        data: I,
-        case: Case,
-        condition: &'a [Condition<'a>],
-        select: Option<&'a [String]>,
-        wildcard: Option<&'a Value>,
-    ) -> impl Iterator<Item = ObjectMap> + 'a
-    where
-        I: Iterator<Item = &'a Vec<Value>> + 'a,
-    {
-        data.filter_map(move |row| {
-            if self.row_equals(case, condition, row, wildcard) {
-                Some(self.add_columns(select, row))
-            } else {
-                None
-            }
-        })
-    }
-
-    fn indexed<'a>(
-        &'a self,
-        case: Case,
-        condition: &'a [Condition<'a>],
-        handle: IndexHandle,
-    ) -> Result<Option<&'a Vec<usize>>, Error> {
-        // The index to use has been passed, we can use this to search the data.
-        // We are assuming that the caller has passed an index that represents the fields
-        // being passed in the condition.
-        let mut hash = seahash::SeaHasher::default();
-
-        for header in self.headers.iter() {
-            if let Some(Condition::Equals { value, .. }) = condition.iter().find(
-                |condition| matches!(condition, Condition::Equals { field, .. } if field == header),
-            ) {
-                hash_value(&mut hash, case, value)?;
-            }
-        }
-
-        let key = hash.finish();
-
-        let IndexHandle(handle) = handle;
-        Ok(self.indexes[handle].2.get(&key))
-    }
-
-    fn indexed_with_wildcard<'a>(
-        &'a self,
-        case: Case,
-        wildcard: &'a Value,
-        condition: &'a [Condition<'a>],
-        handle: IndexHandle,
-    ) -> Result<Option<&'a Vec<usize>>, Error> {
-        if let Some(result) = self.indexed(case, condition, handle)? {
-            return Ok(Some(result));
-        }
-
-        // If lookup fails and a wildcard is provided, compute hash for the wildcard
-        let mut wildcard_hash = seahash::SeaHasher::default();
-        for header in self.headers.iter() {
-            if condition.iter().any(
-                |condition| matches!(condition, Condition::Equals { field, .. } if field == header),
-            ) {
-                hash_value(&mut wildcard_hash, case, wildcard)?;
-            }
-        }
-
-        let wildcard_key = wildcard_hash.finish();
-        let IndexHandle(handle) = handle;
-        Ok(self.indexes[handle].2.get(&wildcard_key))
-    }
-}
-
-/// Adds the bytes from the given value to the hash.
-/// Each field is terminated by a `0` value to separate the fields
-fn hash_value(hasher: &mut seahash::SeaHasher, case: Case, value: &Value) -> Result<(), Error> {
-    match value {
-        Value::Bytes(bytes) => match case {
-            Case::Sensitive => hasher.write(bytes),
-            Case::Insensitive => hasher.write(
-                std::str::from_utf8(bytes)
-                    .map_err(|source| Error::InvalidUtfInColumn { source })?
-                    .to_lowercase()
-                    .as_bytes(),
-            ),
-        },
-        value => {
-            let bytes: bytes::Bytes = value
-                .encode_as_bytes()
-                .map_err(|details| Error::FailedToEncodeValue { details })?;
-            hasher.write(&bytes);
-        }
-    }
-
-    hasher.write_u8(0);
-
-    Ok(())
-}
-
-/// Returns an error if the iterator doesn't yield exactly one result.
-fn single_or_err<I, T>(mut iter: T) -> Result<I, Error>
-where
-    T: Iterator<Item = I>,
-{
-    let result = iter.next();
-
-    if iter.next().is_some() {
-        // More than one row has been found.
-        Err(Error::MoreThanOneRowFound)
-    } else {
-        result.ok_or(Error::NoRowsFound)
-    }
 }
 
 impl Table for File {
@@ -572,25 +288,8 @@ impl Table for File {
         wildcard: Option<&Value>,
         index: Option<IndexHandle>,
     ) -> Result<ObjectMap, Error> {
-        match index {
-            None => {
-                // No index has been passed so we need to do a Sequential Scan.
-                single_or_err(self.sequential(self.data.iter(), case, condition, select, wildcard))
-            }
-            Some(handle) => {
-                let result = if let Some(wildcard) = wildcard {
-                    self.indexed_with_wildcard(case, wildcard, condition, handle)?
-                } else {
-                    self.indexed(case, condition, handle)?
-                }
-                .ok_or(Error::NoRowsFound)?
-                .iter()
-                .map(|idx| &self.data[*idx]);
-
-                // Perform a sequential scan over the indexed result.
-                single_or_err(self.sequential(result, case, condition, select, wildcard))
-            }
-        }
+        self.dataset
+            .find_table_row(case, condition, select, wildcard, index)
     }
 
     fn find_table_rows<'a>(
@@ -601,71 +300,17 @@ impl Table for File {
         wildcard: Option<&Value>,
         index: Option<IndexHandle>,
     ) -> Result<Vec<ObjectMap>, Error> {
-        match index {
-            None => {
-                // No index has been passed so we need to do a Sequential Scan.
-                Ok(self
-                    .sequential(self.data.iter(), case, condition, select, wildcard)
-                    .collect())
-            }
-            Some(handle) => {
-                // Perform a sequential scan over the indexed result.
-                let indexed_result = if let Some(wildcard) = wildcard {
-                    self.indexed_with_wildcard(case, wildcard, condition, handle)?
-                } else {
-                    self.indexed(case, condition, handle)?
-                };
-
-                Ok(self
-                    .sequential(
-                        indexed_result
-                            .iter()
-                            .flat_map(|results| results.iter().map(|idx| &self.data[*idx])),
-                        case,
-                        condition,
-                        select,
-                        wildcard,
-                    )
-                    .collect())
-            }
-        }
+        self.dataset
+            .find_table_rows(case, condition, select, wildcard, index)
     }
 
     fn add_index(&mut self, case: Case, fields: &[&str]) -> Result<IndexHandle, Error> {
-        let normalized = self.normalize_index_fields(fields)?;
-        match self
-            .indexes
-            .iter()
-            .position(|index| index.0 == case && index.1 == normalized)
-        {
-            Some(pos) => {
-                // This index already exists
-                Ok(IndexHandle(pos))
-            }
-            None => {
-                let index = self.index_data(&normalized, case)?;
-                self.indexes.push((case, normalized, index));
-                // The returned index handle is the position of the index in our list of indexes.
-                Ok(IndexHandle(self.indexes.len() - 1))
-            }
-        }
+        self.dataset.add_index(case, fields)
     }
 
     /// Returns a list of the field names that are in each index
     fn index_fields(&self) -> Vec<(Case, Vec<String>)> {
-        self.indexes
-            .iter()
-            .map(|index| {
-                let (case, fields, _) = index;
-                (
-                    *case,
-                    fields
-                        .iter()
-                        .map(|idx| self.headers[*idx].clone())
-                        .collect::<Vec<_>>(),
-                )
-            })
-            .collect::<Vec<_>>()
+        self.dataset.index_fields()
     }
 
     /// Checks the modified timestamp of the data file to see if data has changed.
@@ -681,14 +326,16 @@ impl std::fmt::Debug for File {
         write!(
             f,
             "File {} row(s) {} index(es)",
-            self.data.len(),
-            self.indexes.len()
+            self.dataset.len(),
+            self.dataset.index_count()
         )
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::hash::Hasher;
+
     use chrono::{TimeZone, Timelike};
 
     use super::*;
@@ -932,7 +579,7 @@ mod tests {
         let handle2 = file.add_index(Case::Sensitive, &["field3", "field2"]);
 
         assert_eq!(handle1, handle2);
-        assert_eq!(1, file.indexes.len());
+        assert_eq!(1, file.dataset.index_count());
     }
 
     #[test]
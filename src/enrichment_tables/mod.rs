@@ -9,8 +9,12 @@ use crate::config::{
     ComponentKey, EnrichmentTableConfig, GenerateConfig, GlobalOptions, SinkConfig, SourceConfig,
 };
 
+mod dataset;
 pub mod file;
 
+#[cfg(feature = "enrichment-tables-database")]
+pub mod database;
+
 #[cfg(feature = "enrichment-tables-memory")]
 pub mod memory;
 
@@ -20,12 +24,17 @@ pub mod geoip;
 #[cfg(feature = "enrichment-tables-mmdb")]
 pub mod mmdb;
 
+#[cfg(feature = "enrichment-tables-sqlite")]
+pub mod sqlite;
+
 /// Configuration options for an [enrichment table](https://vector.dev/docs/reference/glossary/#enrichment-tables) to be used in a
 /// [`remap`](https://vector.dev/docs/reference/configuration/transforms/remap/) transform. Currently supported are:
 ///
 /// * [CSV](https://en.wikipedia.org/wiki/Comma-separated_values) files
 /// * [MaxMind](https://www.maxmind.com/en/home) databases
 /// * In-memory storage
+/// * The results of SQL queries
+/// * [SQLite](https://www.sqlite.org/) database files
 ///
 /// For the lookup in the enrichment tables to be as performant as possible, the data is indexed according
 /// to the fields that are used in the search. Note that indices can only be created for fields for which an
@@ -53,6 +62,11 @@ pub enum EnrichmentTables {
     #[cfg(feature = "enrichment-tables-memory")]
     Memory(memory::MemoryConfig),
 
+    /// Exposes the result of a query against a SQL database as an enrichment table. The query is
+    /// run again periodically to refresh the table.
+    #[cfg(feature = "enrichment-tables-database")]
+    Database(database::DatabaseConfig),
+
     /// Exposes data from a [MaxMind][maxmind] [GeoIP2][geoip2] database as an enrichment table.
     ///
     /// [maxmind]: https://www.maxmind.com/
@@ -65,6 +79,13 @@ pub enum EnrichmentTables {
     /// [maxmind]: https://www.maxmind.com/
     #[cfg(feature = "enrichment-tables-mmdb")]
     Mmdb(mmdb::MmdbConfig),
+
+    /// Exposes a table of a [SQLite][sqlite] database file as an enrichment table. Lookups are
+    /// queries against the file, so the table is not loaded into memory.
+    ///
+    /// [sqlite]: https://www.sqlite.org/
+    #[cfg(feature = "enrichment-tables-sqlite")]
+    Sqlite(sqlite::SqliteConfig),
 }
 
 // Manual NamedComponent impl required because enum_dispatch doesn't support it yet.
@@ -74,10 +95,14 @@ impl vector_lib::configurable::NamedComponent for EnrichmentTables {
             Self::File(config) => config.get_component_name(),
             #[cfg(feature = "enrichment-tables-memory")]
             Self::Memory(config) => config.get_component_name(),
+            #[cfg(feature = "enrichment-tables-database")]
+            Self::Database(config) => config.get_component_name(),
             #[cfg(feature = "enrichment-tables-geoip")]
             Self::Geoip(config) => config.get_component_name(),
             #[cfg(feature = "enrichment-tables-mmdb")]
             Self::Mmdb(config) => config.get_component_name(),
+            #[cfg(feature = "enrichment-tables-sqlite")]
+            Self::Sqlite(config) => config.get_component_name(),
         }
     }
 }
@@ -102,10 +127,14 @@ impl EnrichmentTables {
             EnrichmentTables::File(file_config) => vec![&file_config.file.path],
             #[cfg(feature = "enrichment-tables-memory")]
             EnrichmentTables::Memory(_) => vec![],
+            #[cfg(feature = "enrichment-tables-database")]
+            EnrichmentTables::Database(_) => vec![],
             #[cfg(feature = "enrichment-tables-geoip")]
             EnrichmentTables::Geoip(geoip_config) => vec![&geoip_config.path],
             #[cfg(feature = "enrichment-tables-mmdb")]
             EnrichmentTables::Mmdb(mmdb_config) => vec![&mmdb_config.path],
+            #[cfg(feature = "enrichment-tables-sqlite")]
+            EnrichmentTables::Sqlite(_) => vec![],
         }
     }
 }
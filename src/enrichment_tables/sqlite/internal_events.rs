@@ -0,0 +1,20 @@
+use metrics::counter;
+use vector_lib::{NamedInternalEvent, internal_event::InternalEvent};
+
+#[derive(Debug, NamedInternalEvent)]
+pub(crate) struct SqliteEnrichmentTableCacheHit;
+
+impl InternalEvent for SqliteEnrichmentTableCacheHit {
+    fn emit(self) {
+        counter!("sqlite_enrichment_table_cache_hits_total").increment(1);
+    }
+}
+
+#[derive(Debug, NamedInternalEvent)]
+pub(crate) struct SqliteEnrichmentTableCacheMiss;
+
+impl InternalEvent for SqliteEnrichmentTableCacheMiss {
+    fn emit(self) {
+        counter!("sqlite_enrichment_table_cache_misses_total").increment(1);
+    }
+}
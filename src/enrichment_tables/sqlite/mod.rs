@@ -0,0 +1,596 @@
+//! Handles enrichment tables for `type = sqlite`.
+//! Enrichment data is looked up with a query against a table of a [SQLite][sqlite] database file
+//! for each search, rather than loaded into memory.
+//!
+//! [sqlite]: https://www.sqlite.org/
+use std::{
+    future::Future,
+    num::{NonZeroU64, NonZeroUsize},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use lru::LruCache;
+use sqlx::{
+    Column, Row, Sqlite, SqlitePool, TypeInfo, ValueRef,
+    query::Query,
+    sqlite::{SqliteArguments, SqliteConnectOptions, SqlitePoolOptions, SqliteRow},
+};
+use tokio::runtime::{Handle, RuntimeFlavor};
+use vector_lib::{
+    configurable::configurable_component,
+    enrichment::{Case, Condition, Error, IndexHandle, Table},
+};
+use vrl::value::{ObjectMap, Value};
+
+use self::internal_events::{SqliteEnrichmentTableCacheHit, SqliteEnrichmentTableCacheMiss};
+use crate::config::{EnrichmentTableConfig, GenerateConfig};
+
+mod internal_events;
+
+/// The number of connections that lookups are run on in parallel.
+const LOOKUP_CONNECTIONS: u32 = 4;
+
+/// Configuration for the `sqlite` enrichment table.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[configurable_component(enrichment_table("sqlite"))]
+#[serde(deny_unknown_fields)]
+pub struct SqliteConfig {
+    /// The path of the SQLite database file.
+    ///
+    /// The file is opened read-only. Changes written to it by other processes are visible to the
+    /// next lookup.
+    #[configurable(metadata(docs::examples = "/var/lib/vector/asn.db"))]
+    pub path: PathBuf,
+
+    /// The table of the database that is searched.
+    ///
+    /// Each search is a query against the table, so the columns that are searched should be
+    /// covered by indexes of the table. Dates are compared with the `julianday` function, so date
+    /// columns must hold dates in one of the time formats that SQLite supports.
+    #[configurable(metadata(docs::examples = "asn"))]
+    pub table: String,
+
+    /// Caches the results of lookups, so that lookups of the same rows do not query the database
+    /// again until the results expire.
+    #[configurable(derived)]
+    pub cache: Option<SqliteCacheConfig>,
+}
+
+/// Configuration of the cache of lookup results.
+#[configurable_component]
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SqliteCacheConfig {
+    /// The maximum number of lookup results that are cached.
+    ///
+    /// When the cache is full, the least recently used result is evicted.
+    #[configurable(metadata(docs::examples = 10000))]
+    pub max_entries: NonZeroUsize,
+
+    /// The time that lookup results are cached for, in seconds.
+    ///
+    /// Changes to the database are not visible to cached lookups until their results expire.
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    #[configurable(metadata(docs::examples = 60))]
+    pub ttl_secs: NonZeroU64,
+}
+
+/// The results of recent lookups, keyed by their query and parameters.
+struct LookupCache {
+    entries: Mutex<LruCache<String, (Instant, Vec<ObjectMap>)>>,
+    ttl: Duration,
+}
+
+impl LookupCache {
+    fn new(config: &SqliteCacheConfig) -> Self {
+        Self {
+            entries: Mutex::new(LruCache::new(config.max_entries)),
+            ttl: Duration::from_secs(config.ttl_secs.get()),
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<Vec<ObjectMap>> {
+        let mut entries = self.entries.lock().expect("mutex poisoned");
+        match entries.get(key) {
+            Some((cached_at, rows)) if cached_at.elapsed() < self.ttl => {
+                emit!(SqliteEnrichmentTableCacheHit);
+                return Some(rows.clone());
+            }
+            Some(_) => {
+                entries.pop(key);
+            }
+            None => {}
+        }
+        emit!(SqliteEnrichmentTableCacheMiss);
+        None
+    }
+
+    fn insert(&self, key: String, rows: Vec<ObjectMap>) {
+        let mut entries = self.entries.lock().expect("mutex poisoned");
+        entries.put(key, (Instant::now(), rows));
+    }
+}
+
+impl GenerateConfig for SqliteConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            path: "/path/to/database.db".into(),
+            table: "table".to_owned(),
+            cache: None,
+        })
+        .unwrap()
+    }
+}
+
+impl EnrichmentTableConfig for SqliteConfig {
+    async fn build(
+        &self,
+        _: &crate::config::GlobalOptions,
+    ) -> crate::Result<Box<dyn Table + Send + Sync>> {
+        Ok(Box::new(SqliteTable::open(self.clone()).await?))
+    }
+}
+
+/// A struct that implements [vector_lib::enrichment::Table] to handle looking up enrichment data
+/// in a SQLite database file.
+#[derive(Clone)]
+pub struct SqliteTable {
+    config: SqliteConfig,
+    pool: SqlitePool,
+    columns: Vec<String>,
+    indexes: Vec<(Case, Vec<String>)>,
+    cache: Option<Arc<LookupCache>>,
+}
+
+impl SqliteTable {
+    /// Opens the database file of `config`, checking that it has the configured table.
+    pub async fn open(config: SqliteConfig) -> crate::Result<Self> {
+        let options = SqliteConnectOptions::new()
+            .filename(&config.path)
+            .read_only(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(LOOKUP_CONNECTIONS)
+            .connect_with(options)
+            .await?;
+        let columns = sqlx::query_scalar("SELECT name FROM pragma_table_info(?) ORDER BY cid")
+            .bind(&config.table)
+            .fetch_all(&pool)
+            .await?;
+        if columns.is_empty() {
+            return Err(format!("Table {:?} does not exist.", config.table).into());
+        }
+
+        Ok(Self {
+            cache: config
+                .cache
+                .as_ref()
+                .map(|cache| Arc::new(LookupCache::new(cache))),
+            config,
+            pool,
+            columns,
+            indexes: Vec::new(),
+        })
+    }
+
+    /// Looks up the rows matching `condition`, returning at most `limit` rows, from the cache
+    /// or else with a query of the database.
+    fn lookup(
+        &self,
+        case: Case,
+        condition: &[Condition<'_>],
+        select: Option<&[String]>,
+        wildcard: Option<&Value>,
+        limit: Option<usize>,
+    ) -> Result<Vec<ObjectMap>, Error> {
+        let sql = self.select_sql(case, condition, select, wildcard, limit);
+        let Some(cache) = &self.cache else {
+            return self.query(&sql, condition, wildcard);
+        };
+
+        // The query covers the case, selected columns and limit, so with the parameters it
+        // identifies the lookup.
+        let key = format!("{sql}\n{condition:?}\n{wildcard:?}");
+        if let Some(rows) = cache.get(&key) {
+            return Ok(rows);
+        }
+        let rows = self.query(&sql, condition, wildcard)?;
+        cache.insert(key, rows.clone());
+        Ok(rows)
+    }
+
+    /// Runs `sql` with the parameters of `condition`.
+    ///
+    /// Searches are synchronous, so the calling thread waits for the query to complete. Queries
+    /// run on a pool of connections, so that concurrent searches do not wait for each other.
+    fn query(
+        &self,
+        sql: &str,
+        condition: &[Condition<'_>],
+        wildcard: Option<&Value>,
+    ) -> Result<Vec<ObjectMap>, Error> {
+        let mut query = sqlx::query(sql);
+        for condition in condition {
+            query = match condition {
+                Condition::Equals { value, .. } => {
+                    let query = bind_value(query, value);
+                    match wildcard {
+                        Some(wildcard) => bind_value(query, wildcard),
+                        None => query,
+                    }
+                }
+                Condition::BetweenDates { from, to, .. } => {
+                    query.bind(date_param(from)).bind(date_param(to))
+                }
+                Condition::FromDate { from, .. } => query.bind(date_param(from)),
+                Condition::ToDate { to, .. } => query.bind(date_param(to)),
+            };
+        }
+
+        let rows = block_on(query.fetch_all(&self.pool)).map_err(query_failed)?;
+        rows.iter().map(decode_row).collect()
+    }
+
+    fn select_sql(
+        &self,
+        case: Case,
+        condition: &[Condition<'_>],
+        select: Option<&[String]>,
+        wildcard: Option<&Value>,
+        limit: Option<usize>,
+    ) -> String {
+        let columns = match select {
+            Some(fields) => fields
+                .iter()
+                .map(|field| quote_identifier(field))
+                .collect::<Vec<_>>()
+                .join(", "),
+            None => "*".to_owned(),
+        };
+        let collate = match case {
+            Case::Sensitive => "",
+            Case::Insensitive => " COLLATE NOCASE",
+        };
+        let filters = condition
+            .iter()
+            .map(|condition| match condition {
+                Condition::Equals { field, .. } => {
+                    let field = quote_identifier(field);
+                    match wildcard {
+                        Some(_) => format!("({field} = ?{collate} OR {field} = ?{collate})"),
+                        None => format!("{field} = ?{collate}"),
+                    }
+                }
+                Condition::BetweenDates { field, .. } => format!(
+                    "julianday({}) BETWEEN julianday(?) AND julianday(?)",
+                    quote_identifier(field)
+                ),
+                Condition::FromDate { field, .. } => {
+                    format!("julianday({}) >= julianday(?)", quote_identifier(field))
+                }
+                Condition::ToDate { field, .. } => {
+                    format!("julianday({}) <= julianday(?)", quote_identifier(field))
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut sql = format!(
+            "SELECT {columns} FROM {}",
+            quote_identifier(&self.config.table)
+        );
+        if !filters.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&filters.join(" AND "));
+        }
+        if let Some(limit) = limit {
+            sql.push_str(&format!(" LIMIT {limit}"));
+        }
+        sql
+    }
+}
+
+/// Waits for `future` on the calling thread.
+///
+/// On a worker thread of a multi-threaded runtime, the thread is first handed over with
+/// `block_in_place`, so that the other tasks of the worker are not stalled by the query.
+fn block_on<F: Future>(future: F) -> F::Output {
+    match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(|| handle.block_on(future))
+        }
+        _ => futures::executor::block_on(future),
+    }
+}
+
+fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+fn date_param(date: &DateTime<Utc>) -> String {
+    date.to_rfc3339()
+}
+
+fn bind_value<'q>(
+    query: Query<'q, Sqlite, SqliteArguments<'q>>,
+    value: &Value,
+) -> Query<'q, Sqlite, SqliteArguments<'q>> {
+    match value {
+        Value::Null => query.bind(None::<String>),
+        Value::Boolean(value) => query.bind(*value),
+        Value::Integer(value) => query.bind(*value),
+        Value::Float(value) => query.bind(value.into_inner()),
+        Value::Timestamp(value) => query.bind(date_param(value)),
+        Value::Bytes(_) => query.bind(value.to_string_lossy().into_owned()),
+        _ => query.bind(value.to_string()),
+    }
+}
+
+fn decode_row(row: &SqliteRow) -> Result<ObjectMap, Error> {
+    row.columns()
+        .iter()
+        .map(|column| {
+            let index = column.ordinal();
+            let kind = {
+                let value = row.try_get_raw(index).map_err(query_failed)?;
+                if value.is_null() {
+                    return Ok((column.name().into(), Value::Null));
+                }
+                value.type_info().name().to_owned()
+            };
+            let value = match kind.as_str() {
+                "INTEGER" => row.try_get::<i64, _>(index).map(Value::from),
+                "REAL" => row.try_get::<f64, _>(index).map(Value::from_f64_or_zero),
+                "BLOB" => row
+                    .try_get::<Vec<u8>, _>(index)
+                    .map(|bytes| Value::Bytes(bytes.into())),
+                _ => row.try_get::<String, _>(index).map(Value::from),
+            }
+            .map_err(query_failed)?;
+            Ok((column.name().into(), value))
+        })
+        .collect()
+}
+
+fn query_failed(error: sqlx::Error) -> Error {
+    Error::QueryFailed {
+        details: error.to_string(),
+    }
+}
+
+impl Table for SqliteTable {
+    /// Search the enrichment table data with the given condition.
+    /// All conditions must match (AND).
+    ///
+    /// # Errors
+    /// Errors if no rows, or more than 1 row is found.
+    fn find_table_row<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&[String]>,
+        wildcard: Option<&Value>,
+        _: Option<IndexHandle>,
+    ) -> Result<ObjectMap, Error> {
+        let mut rows = self.lookup(case, condition, select, wildcard, Some(2))?;
+
+        match rows.pop() {
+            Some(row) if rows.is_empty() => Ok(row),
+            Some(_) => Err(Error::MoreThanOneRowFound),
+            None => Err(Error::NoRowsFound),
+        }
+    }
+
+    /// Search the enrichment table data with the given condition.
+    /// All conditions must match (AND).
+    /// Can return multiple matched records
+    fn find_table_rows<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&[String]>,
+        wildcard: Option<&Value>,
+        _: Option<IndexHandle>,
+    ) -> Result<Vec<ObjectMap>, Error> {
+        self.lookup(case, condition, select, wildcard, None)
+    }
+
+    /// Hints to the enrichment table what data is going to be searched. Searches are answered by
+    /// the indexes of the database, so this only checks that the fields are columns of the table.
+    ///
+    /// # Errors
+    /// Errors if the fields are not in the table.
+    fn add_index(&mut self, case: Case, fields: &[&str]) -> Result<IndexHandle, Error> {
+        let missing = fields
+            .iter()
+            .filter(|field| !self.columns.iter().any(|column| column == *field))
+            .map(|field| field.to_string())
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            return Err(Error::MissingDatasetFields { fields: missing });
+        }
+
+        self.indexes
+            .push((case, fields.iter().map(|field| field.to_string()).collect()));
+        Ok(IndexHandle(self.indexes.len() - 1))
+    }
+
+    /// Returns a list of the field names that are in each index
+    fn index_fields(&self) -> Vec<(Case, Vec<String>)> {
+        self.indexes.clone()
+    }
+
+    /// Lookups always query the current contents of the file, so the table never needs
+    /// reloading.
+    fn needs_reload(&self) -> bool {
+        false
+    }
+}
+
+impl std::fmt::Debug for SqliteTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "SQLite table {} in {}",
+            self.config.table,
+            self.config.path.display()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use sqlx::Executor;
+
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    async fn table(cache: Option<SqliteCacheConfig>) -> SqliteTable {
+        let path = temp_dir().join("asn.db");
+        let options = SqliteConnectOptions::new()
+            .filename(&path)
+            .create_if_missing(true);
+        let pool = SqlitePool::connect_with(options).await.unwrap();
+        pool.execute(
+            "CREATE TABLE asn (prefix TEXT, asn INTEGER, name TEXT, since TEXT);
+             INSERT INTO asn VALUES
+                 ('1.1.1.0/24', 13335, 'Cloudflare', '2018-04-01'),
+                 ('8.8.8.0/24', 15169, 'Google', '2014-01-01'),
+                 ('8.8.4.0/24', 15169, 'Google', '2014-01-01');",
+        )
+        .await
+        .unwrap();
+
+        SqliteTable::open(SqliteConfig {
+            path,
+            table: "asn".to_owned(),
+            cache,
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn finds_rows() {
+        let table = table(None).await;
+        let condition = [Condition::Equals {
+            field: "prefix",
+            value: Value::from("1.1.1.0/24"),
+        }];
+
+        assert_eq!(
+            Ok(ObjectMap::from([
+                ("prefix".into(), Value::from("1.1.1.0/24")),
+                ("asn".into(), Value::from(13335)),
+                ("name".into(), Value::from("Cloudflare")),
+                ("since".into(), Value::from("2018-04-01")),
+            ])),
+            table.find_table_row(Case::Sensitive, &condition, None, None, None)
+        );
+
+        let condition = [
+            Condition::Equals {
+                field: "name",
+                value: Value::from("google"),
+            },
+            Condition::FromDate {
+                field: "since",
+                from: Utc.with_ymd_and_hms(2010, 1, 1, 0, 0, 0).unwrap(),
+            },
+        ];
+        let select = ["prefix".to_owned()];
+        assert_eq!(
+            Ok(vec![
+                ObjectMap::from([("prefix".into(), Value::from("8.8.8.0/24"))]),
+                ObjectMap::from([("prefix".into(), Value::from("8.8.4.0/24"))]),
+            ]),
+            table.find_table_rows(Case::Insensitive, &condition, Some(&select), None, None)
+        );
+        assert_eq!(
+            Err(Error::MoreThanOneRowFound),
+            table.find_table_row(Case::Insensitive, &condition, None, None, None)
+        );
+        assert_eq!(
+            Err(Error::NoRowsFound),
+            table.find_table_row(Case::Sensitive, &condition, None, None, None)
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn finds_rows_concurrently() {
+        let table = Arc::new(table(None).await);
+        let lookups = (0..8).map(|_| {
+            let table = Arc::clone(&table);
+            tokio::spawn(async move {
+                let condition = [Condition::Equals {
+                    field: "asn",
+                    value: Value::from(13335),
+                }];
+                table
+                    .find_table_row(Case::Sensitive, &condition, None, None, None)
+                    .map(|row| row["name"].clone())
+            })
+        });
+
+        for lookup in futures::future::join_all(lookups).await {
+            assert_eq!(lookup.unwrap(), Ok(Value::from("Cloudflare")));
+        }
+    }
+
+    #[tokio::test]
+    async fn checks_index_fields() {
+        let mut table = table(None).await;
+
+        assert_eq!(
+            Ok(IndexHandle(0)),
+            table.add_index(Case::Sensitive, &["prefix"])
+        );
+        assert_eq!(
+            Err(Error::MissingDatasetFields {
+                fields: vec!["region".to_owned()]
+            }),
+            table.add_index(Case::Sensitive, &["asn", "region"])
+        );
+        assert_eq!(
+            table.index_fields(),
+            [(Case::Sensitive, vec!["prefix".to_owned()])]
+        );
+    }
+
+    #[tokio::test]
+    async fn caches_lookups() {
+        let mut table = table(Some(SqliteCacheConfig {
+            max_entries: NonZeroUsize::new(10).unwrap(),
+            ttl_secs: NonZeroU64::new(3600).unwrap(),
+        }))
+        .await;
+        let condition = [Condition::Equals {
+            field: "asn",
+            value: Value::from(13335),
+        }];
+        let select = ["name".to_owned()];
+        let expected = Ok(ObjectMap::from([(
+            "name".into(),
+            Value::from("Cloudflare"),
+        )]));
+
+        assert_eq!(
+            expected,
+            table.find_table_row(Case::Sensitive, &condition, Some(&select), None, None)
+        );
+        // Replace the pool with one of an empty database, so that only cached lookups find rows.
+        table.pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+
+        assert_eq!(
+            expected,
+            table.find_table_row(Case::Sensitive, &condition, Some(&select), None, None)
+        );
+        assert!(
+            table
+                .find_table_row(Case::Sensitive, &condition, None, None, None)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use metrics::counter;
+use vector_lib::NamedInternalEvent;
+use vector_lib::internal_event::{
+    ComponentEventsDropped, InternalEvent, UNINTENTIONAL, error_stage, error_type,
+};
+
+/// Emitted when the table rendered for an event is not one that the sink may write to.
+#[derive(Debug, NamedInternalEvent)]
+pub struct DatabaseTableRejectedError<'a> {
+    pub table: &'a str,
+    pub reason: &'static str,
+}
+
+impl InternalEvent for DatabaseTableRejectedError<'_> {
+    fn emit(self) {
+        error!(
+            message = "Rejected rendered table.",
+            table = %self.table,
+            reason = %self.reason,
+            error_type = error_type::CONDITION_FAILED,
+            stage = error_stage::PROCESSING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_type" => error_type::CONDITION_FAILED,
+            "stage" => error_stage::PROCESSING,
+        )
+        .increment(1);
+        emit!(ComponentEventsDropped::<UNINTENTIONAL> {
+            count: 1,
+            reason: self.reason,
+        });
+    }
+}
+
+/// Emitted when an event has no value for a column that cannot be `NULL`.
+#[derive(Debug, NamedInternalEvent)]
+pub struct DatabaseMissingColumnValueError<'a> {
+    pub column: &'a str,
+}
+
+impl InternalEvent for DatabaseMissingColumnValueError<'_> {
+    fn emit(self) {
+        let reason = "Event has no value for a column that is not nullable.";
+        error!(
+            message = reason,
+            column = %self.column,
+            error_type = error_type::CONDITION_FAILED,
+            stage = error_stage::PROCESSING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_type" => error_type::CONDITION_FAILED,
+            "stage" => error_stage::PROCESSING,
+        )
+        .increment(1);
+        emit!(ComponentEventsDropped::<UNINTENTIONAL> { count: 1, reason });
+    }
+}
+
+/// Emitted when a row fails to insert on its own after its batch was split, and is written to
+/// the dead-letter table instead.
+#[derive(Debug, NamedInternalEvent)]
+pub struct DatabaseRowDeadLetteredError<'a, E> {
+    pub table: &'a str,
+    pub dead_letter_table: &'a str,
+    pub error: &'a E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for DatabaseRowDeadLetteredError<'_, E> {
+    fn emit(self) {
+        error!(
+            message = "Row could not be inserted, writing it to the dead-letter table.",
+            table = %self.table,
+            dead_letter_table = %self.dead_letter_table,
+            error = %self.error,
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::SENDING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::SENDING,
+        )
+        .increment(1);
+    }
+}
+
+/// Emitted when the expression of a column fails for an event.
+#[derive(Debug, NamedInternalEvent)]
+pub struct DatabaseColumnExpressionError<'a, E> {
+    pub column: &'a str,
+    pub error: &'a E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for DatabaseColumnExpressionError<'_, E> {
+    fn emit(self) {
+        error!(
+            message = "Column expression failed.",
+            column = %self.column,
+            error = %self.error,
+            error_type = error_type::SCRIPT_FAILED,
+            stage = error_stage::PROCESSING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_type" => error_type::SCRIPT_FAILED,
+            "stage" => error_stage::PROCESSING,
+        )
+        .increment(1);
+    }
+}
+
+/// Emitted when a metric cannot be written as a row.
+#[derive(Debug, NamedInternalEvent)]
+pub struct DatabaseMetricRejectedError<'a> {
+    pub metric: &'a str,
+    pub reason: &'static str,
+}
+
+impl InternalEvent for DatabaseMetricRejectedError<'_> {
+    fn emit(self) {
+        error!(
+            message = "Rejected metric.",
+            metric = %self.metric,
+            reason = %self.reason,
+            error_type = error_type::CONDITION_FAILED,
+            stage = error_stage::PROCESSING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_type" => error_type::CONDITION_FAILED,
+            "stage" => error_stage::PROCESSING,
+        )
+        .increment(1);
+        emit!(ComponentEventsDropped::<UNINTENTIONAL> {
+            count: 1,
+            reason: self.reason,
+        });
+    }
+}
+
+/// Emitted when a row fails to insert on its own after its batch was split.
+#[derive(Debug, NamedInternalEvent)]
+pub struct DatabaseRowRejectedError<'a, E> {
+    pub table: &'a str,
+    pub error: &'a E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for DatabaseRowRejectedError<'_, E> {
+    fn emit(self) {
+        let reason = "Row could not be inserted.";
+        error!(
+            message = reason,
+            table = %self.table,
+            error = %self.error,
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::SENDING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::SENDING,
+        )
+        .increment(1);
+        emit!(ComponentEventsDropped::<UNINTENTIONAL> { count: 1, reason });
+    }
+}
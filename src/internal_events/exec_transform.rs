@@ -0,0 +1,166 @@
+use metrics::counter;
+use vector_lib::{
+    NamedInternalEvent,
+    internal_event::{
+        ComponentEventsDropped, InternalEvent, UNINTENTIONAL, error_stage, error_type,
+    },
+};
+
+use super::prelude::io_error_code;
+
+#[derive(Debug, NamedInternalEvent)]
+pub struct ExecTransformSpawnError<'a> {
+    pub command: &'a str,
+    pub error: std::io::Error,
+}
+
+impl InternalEvent for ExecTransformSpawnError<'_> {
+    fn emit(self) {
+        error!(
+            message = "Unable to spawn process.",
+            command = %self.command,
+            error = ?self.error,
+            error_type = error_type::COMMAND_FAILED,
+            error_code = %io_error_code(&self.error),
+            stage = error_stage::PROCESSING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_type" => error_type::COMMAND_FAILED,
+            "error_code" => io_error_code(&self.error),
+            "stage" => error_stage::PROCESSING,
+        )
+        .increment(1);
+    }
+}
+
+#[derive(Debug, NamedInternalEvent)]
+pub struct ExecTransformExitedError<'a> {
+    pub command: &'a str,
+    pub exit_status: Option<i32>,
+}
+
+impl InternalEvent for ExecTransformExitedError<'_> {
+    fn emit(self) {
+        let exit_status = self
+            .exit_status
+            .map_or_else(|| "unknown".to_owned(), |status| status.to_string());
+        error!(
+            message = "Process exited, respawning.",
+            command = %self.command,
+            exit_status = %exit_status,
+            error_type = error_type::COMMAND_FAILED,
+            stage = error_stage::PROCESSING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_type" => error_type::COMMAND_FAILED,
+            "stage" => error_stage::PROCESSING,
+        )
+        .increment(1);
+    }
+}
+
+#[derive(Debug, NamedInternalEvent)]
+pub struct ExecTransformEncodeError<'a> {
+    pub command: &'a str,
+    pub error: serde_json::Error,
+}
+
+impl InternalEvent for ExecTransformEncodeError<'_> {
+    fn emit(self) {
+        let reason = "Failed to encode event for process.";
+        error!(
+            message = reason,
+            command = %self.command,
+            error = %self.error,
+            error_type = error_type::ENCODER_FAILED,
+            stage = error_stage::PROCESSING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_type" => error_type::ENCODER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        )
+        .increment(1);
+        emit!(ComponentEventsDropped::<UNINTENTIONAL> { count: 1, reason });
+    }
+}
+
+#[derive(Debug, NamedInternalEvent)]
+pub struct ExecTransformWriteError<'a> {
+    pub command: &'a str,
+    pub error: std::io::Error,
+}
+
+impl InternalEvent for ExecTransformWriteError<'_> {
+    fn emit(self) {
+        error!(
+            message = "Failed to write event to process.",
+            command = %self.command,
+            error = %self.error,
+            error_type = error_type::WRITER_FAILED,
+            error_code = %io_error_code(&self.error),
+            stage = error_stage::PROCESSING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_type" => error_type::WRITER_FAILED,
+            "error_code" => io_error_code(&self.error),
+            "stage" => error_stage::PROCESSING,
+        )
+        .increment(1);
+    }
+}
+
+#[derive(Debug, NamedInternalEvent)]
+pub struct ExecTransformReadError<'a> {
+    pub command: &'a str,
+    pub error: std::io::Error,
+}
+
+impl InternalEvent for ExecTransformReadError<'_> {
+    fn emit(self) {
+        error!(
+            message = "Failed to read event from process.",
+            command = %self.command,
+            error = %self.error,
+            error_type = error_type::READER_FAILED,
+            error_code = %io_error_code(&self.error),
+            stage = error_stage::PROCESSING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_type" => error_type::READER_FAILED,
+            "error_code" => io_error_code(&self.error),
+            "stage" => error_stage::PROCESSING,
+        )
+        .increment(1);
+    }
+}
+
+#[derive(Debug, NamedInternalEvent)]
+pub struct ExecTransformParseError<'a> {
+    pub command: &'a str,
+    pub error: crate::Error,
+}
+
+impl InternalEvent for ExecTransformParseError<'_> {
+    fn emit(self) {
+        let reason = "Failed to parse event from process.";
+        error!(
+            message = reason,
+            command = %self.command,
+            error = %self.error,
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        )
+        .increment(1);
+        emit!(ComponentEventsDropped::<UNINTENTIONAL> { count: 1, reason });
+    }
+}
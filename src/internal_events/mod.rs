@@ -30,6 +30,8 @@ mod aws_sqs;
 mod batch;
 mod common;
 mod conditions;
+#[cfg(any(feature = "sinks-mysql", feature = "sinks-sqlite"))]
+mod database;
 #[cfg(feature = "sources-datadog_agent")]
 mod datadog_agent;
 #[cfg(feature = "sinks-datadog_metrics")]
@@ -51,6 +53,8 @@ mod encoding_transcode;
 mod eventstoredb_metrics;
 #[cfg(feature = "sources-exec")]
 mod exec;
+#[cfg(feature = "transforms-exec")]
+mod exec_transform;
 #[cfg(any(
     feature = "sources-file",
     feature = "sources-kubernetes_logs",
@@ -184,6 +188,8 @@ pub(crate) use self::aws_kinesis::*;
 pub(crate) use self::aws_kinesis_firehose::*;
 #[cfg(any(feature = "sources-aws_s3", feature = "sources-aws_sqs",))]
 pub(crate) use self::aws_sqs::*;
+#[cfg(any(feature = "sinks-mysql", feature = "sinks-sqlite"))]
+pub(crate) use self::database::*;
 #[cfg(feature = "sources-datadog_agent")]
 pub(crate) use self::datadog_agent::*;
 #[cfg(feature = "sinks-datadog_metrics")]
@@ -204,6 +210,8 @@ pub(crate) use self::doris::*;
 pub(crate) use self::eventstoredb_metrics::*;
 #[cfg(feature = "sources-exec")]
 pub(crate) use self::exec::*;
+#[cfg(feature = "transforms-exec")]
+pub(crate) use self::exec_transform::*;
 #[cfg(any(feature = "transforms-log_to_metric", feature = "sinks-loki"))]
 pub use self::expansion::*;
 #[cfg(any(
@@ -7,6 +7,8 @@ use crate::{
     signal,
 };
 
+#[cfg(feature = "providers-database")]
+pub mod database;
 pub mod http;
 
 pub type BuildResult = std::result::Result<ConfigBuilder, Vec<String>>;
@@ -17,6 +19,10 @@ pub type BuildResult = std::result::Result<ConfigBuilder, Vec<String>>;
 #[serde(tag = "type", rename_all = "snake_case")]
 #[enum_dispatch(ProviderConfig)]
 pub enum Providers {
+    /// Database.
+    #[cfg(feature = "providers-database")]
+    Database(database::DatabaseConfig),
+
     /// HTTP.
     Http(http::HttpConfig),
 }
@@ -25,6 +31,8 @@ pub enum Providers {
 impl NamedComponent for Providers {
     fn get_component_name(&self) -> &'static str {
         match self {
+            #[cfg(feature = "providers-database")]
+            Self::Database(config) => config.get_component_name(),
             Self::Http(config) => config.get_component_name(),
         }
     }
@@ -0,0 +1,235 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{LazyLock, Mutex},
+    time::{Duration, Instant},
+};
+
+use sqlx::{AnyConnection, Connection, Database, MySql, Row};
+use vector_lib::configurable::{component::GenerateConfig, configurable_component};
+
+use crate::{config::SecretBackend, signal};
+
+/// The secrets read by each backend, shared across configuration reloads.
+static CACHE: LazyLock<Mutex<HashMap<DatabaseBackend, CachedSecrets>>> =
+    LazyLock::new(Default::default);
+
+struct CachedSecrets {
+    fetched_at: Instant,
+    secrets: HashMap<String, String>,
+}
+
+/// Configuration for the `database` secrets backend.
+#[configurable_component(secrets("database"))]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct DatabaseBackend {
+    /// The connection string of the database holding the secrets.
+    ///
+    /// PostgreSQL (`postgres://`), MySQL (`mysql://`), and SQLite (`sqlite://`) databases are
+    /// supported.
+    #[configurable(metadata(docs::examples = "postgres://vector@localhost/credentials"))]
+    pub endpoint: String,
+
+    /// The table or view holding the secrets, one per row.
+    ///
+    /// The name can be qualified with a schema, as in `schema.table`. It and the column names are
+    /// quoted as identifiers, so they can be reserved words such as `key`.
+    #[configurable(metadata(docs::examples = "vector_secrets"))]
+    pub table: String,
+
+    /// The column holding the key of each secret.
+    #[serde(default = "default_key_column")]
+    pub key_column: String,
+
+    /// The column holding the value of each secret.
+    #[serde(default = "default_value_column")]
+    pub value_column: String,
+
+    /// How long the secrets read from the table are reused, in seconds.
+    ///
+    /// Secrets are only read when Vector loads its configuration, so changes to the table are not
+    /// refreshed in the background and take effect on the next configuration reload. Reloads
+    /// within this long of the last read reuse its secrets instead of querying the table again,
+    /// as long as it had all of the requested keys.
+    #[serde(default = "default_cache_ttl_secs")]
+    #[configurable(metadata(docs::human_name = "Cache TTL"))]
+    pub cache_ttl_secs: u64,
+}
+
+fn default_key_column() -> String {
+    "name".to_owned()
+}
+
+fn default_value_column() -> String {
+    "value".to_owned()
+}
+
+const fn default_cache_ttl_secs() -> u64 {
+    300
+}
+
+impl GenerateConfig for DatabaseBackend {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(DatabaseBackend {
+            endpoint: String::from("postgres://vector@localhost/credentials"),
+            table: String::from("vector_secrets"),
+            key_column: default_key_column(),
+            value_column: default_value_column(),
+            cache_ttl_secs: default_cache_ttl_secs(),
+        })
+        .unwrap()
+    }
+}
+
+impl DatabaseBackend {
+    fn cached(&self, secret_keys: &HashSet<String>) -> Option<HashMap<String, String>> {
+        let cache = CACHE.lock().expect("cache lock poisoned");
+        let cached = cache.get(self)?;
+        if cached.fetched_at.elapsed() > Duration::from_secs(self.cache_ttl_secs) {
+            return None;
+        }
+        secret_keys
+            .iter()
+            .map(|key| Some((key.clone(), cached.secrets.get(key)?.clone())))
+            .collect()
+    }
+
+    async fn fetch(&self) -> crate::Result<HashMap<String, String>> {
+        sqlx::any::install_default_drivers();
+        let mut connection = AnyConnection::connect(&self.endpoint).await?;
+        let quote = |name: &str| quote_identifier(connection.backend_name(), name);
+        let query = format!(
+            "SELECT {}, {} FROM {}",
+            quote(&self.key_column),
+            quote(&self.value_column),
+            self.table
+                .split('.')
+                .map(quote)
+                .collect::<Vec<_>>()
+                .join(".")
+        );
+        let rows = sqlx::query(&query).fetch_all(&mut connection).await?;
+        connection.close().await?;
+
+        rows.iter()
+            .map(|row| Ok((row.try_get(0)?, row.try_get(1)?)))
+            .collect::<Result<_, sqlx::Error>>()
+            .map_err(Into::into)
+    }
+}
+
+/// Quotes an identifier for the database named `backend`, escaping any embedded quote characters.
+fn quote_identifier(backend: &str, identifier: &str) -> String {
+    if backend == <MySql as Database>::NAME {
+        format!("`{}`", identifier.replace('`', "``"))
+    } else {
+        format!("\"{}\"", identifier.replace('"', "\"\""))
+    }
+}
+
+impl SecretBackend for DatabaseBackend {
+    async fn retrieve(
+        &mut self,
+        secret_keys: HashSet<String>,
+        _: &mut signal::SignalRx,
+    ) -> crate::Result<HashMap<String, String>> {
+        if let Some(secrets) = self.cached(&secret_keys) {
+            return Ok(secrets);
+        }
+
+        let output = self.fetch().await?;
+        let mut secrets = HashMap::new();
+        for k in secret_keys.into_iter() {
+            match output.get(&k) {
+                Some(secret) if secret.is_empty() => {
+                    return Err(format!(
+                        "value for key '{}' in table '{}' was empty",
+                        k, &self.table
+                    )
+                    .into());
+                }
+                Some(secret) => {
+                    secrets.insert(k, secret.clone());
+                }
+                None => {
+                    return Err(
+                        format!("key '{}' in table '{}' does not exist", k, &self.table).into(),
+                    );
+                }
+            }
+        }
+
+        CACHE.lock().expect("cache lock poisoned").insert(
+            self.clone(),
+            CachedSecrets {
+                fetched_at: Instant::now(),
+                secrets: output,
+            },
+        );
+        Ok(secrets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::AnyPool;
+    use tokio::sync::broadcast;
+
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    #[test]
+    fn quotes_identifiers_per_database() {
+        assert_eq!(quote_identifier("MySQL", "k`ey"), "`k``ey`");
+        assert_eq!(quote_identifier("SQLite", "k\"ey"), "\"k\"\"ey\"");
+    }
+
+    #[tokio::test]
+    async fn retrieves_and_caches_secrets() {
+        let path = temp_dir().join("credentials.db");
+        let endpoint = format!("sqlite://{}?mode=rwc", path.display());
+        sqlx::any::install_default_drivers();
+        let pool = AnyPool::connect(&endpoint).await.unwrap();
+        sqlx::query("CREATE TABLE vector_secrets (\"key\" TEXT, value TEXT)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO vector_secrets VALUES ('user', 'vector'), ('password', 'hunter2')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let mut backend = DatabaseBackend {
+            endpoint,
+            table: "vector_secrets".to_owned(),
+            key_column: "key".to_owned(),
+            value_column: default_value_column(),
+            cache_ttl_secs: 3600,
+        };
+        let (_tx, mut rx) = broadcast::channel(1);
+        let keys = HashSet::from(["password".to_owned()]);
+        let secrets = backend.retrieve(keys.clone(), &mut rx).await.unwrap();
+        assert_eq!(
+            secrets,
+            HashMap::from([("password".into(), "hunter2".into())])
+        );
+
+        sqlx::query("UPDATE vector_secrets SET value = 'changed'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let secrets = backend.retrieve(keys, &mut rx).await.unwrap();
+        assert_eq!(secrets["password"], "hunter2");
+
+        let error = backend
+            .retrieve(HashSet::from(["token".to_owned()]), &mut rx)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "key 'token' in table 'vector_secrets' does not exist"
+        );
+    }
+}
@@ -11,6 +11,8 @@ use crate::{
 
 #[cfg(feature = "secrets-aws-secrets-manager")]
 mod aws_secrets_manager;
+#[cfg(feature = "secrets-database")]
+mod database;
 mod directory;
 mod exec;
 mod file;
@@ -74,6 +76,10 @@ pub enum SecretBackends {
     #[cfg(feature = "secrets-aws-secrets-manager")]
     AwsSecretsManager(aws_secrets_manager::AwsSecretsManagerBackend),
 
+    /// Database.
+    #[cfg(feature = "secrets-database")]
+    Database(database::DatabaseBackend),
+
     /// Test.
     #[configurable(metadata(docs::hidden))]
     Test(test::TestBackend),
@@ -88,6 +94,8 @@ impl vector_lib::configurable::NamedComponent for SecretBackends {
             Self::Exec(config) => config.get_component_name(),
             #[cfg(feature = "secrets-aws-secrets-manager")]
             Self::AwsSecretsManager(config) => config.get_component_name(),
+            #[cfg(feature = "secrets-database")]
+            Self::Database(config) => config.get_component_name(),
             Self::Test(config) => config.get_component_name(),
         }
     }
@@ -0,0 +1,524 @@
+use std::borrow::Cow;
+
+use indexmap::IndexMap;
+use vector_lib::{
+    TimeZone, compile_vrl,
+    configurable::configurable_component,
+    event::{Event, EventStatus, Finalizable, LogEvent, Value},
+    lookup::{
+        OwnedTargetPath, OwnedValuePath, PathPrefix, lookup_v2::ConfigValuePath, owned_value_path,
+    },
+};
+use vrl::compiler::{
+    CompilationResult, CompileConfig, Program, SecretTarget, Target, TypeState, runtime::Runtime,
+};
+
+use super::{Dialect, schema::ColumnDefinition};
+use crate::{
+    format_vrl_diagnostics,
+    internal_events::{DatabaseColumnExpressionError, DatabaseMissingColumnValueError},
+};
+
+/// A column written from an event field.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ColumnConfig {
+    /// The event field that the column is written from.
+    ///
+    /// Defaults to the top-level field named after the column.
+    #[configurable(metadata(docs::examples = "message"))]
+    #[configurable(metadata(docs::examples = "kubernetes.pod_name"))]
+    pub field: Option<ConfigValuePath>,
+
+    /// A [VRL][vrl] expression that the column is written from, instead of a field.
+    ///
+    /// The expression is evaluated against each event, which it cannot modify, in the global
+    /// `timezone`. An event for which the expression fails or returns `null` is written with the
+    /// column default, and is not rejected ahead of the insert when the column is not nullable.
+    ///
+    /// [vrl]: https://vector.dev/docs/reference/vrl
+    #[configurable(metadata(docs::examples = "upcase(string!(.level))"))]
+    #[configurable(metadata(docs::examples = "sha2(string!(.user_email))"))]
+    pub expression: Option<String>,
+
+    /// The SQL type of the column, used when the sink creates the table or adds the column.
+    ///
+    /// When unset, the type is inferred from the values written to the column.
+    #[configurable(metadata(docs::examples = "VARCHAR(255)"))]
+    #[serde(rename = "type")]
+    pub sql_type: Option<String>,
+
+    /// The value written when the event does not have the field, or the field is `null`.
+    pub default: Option<Value>,
+
+    /// Whether the column can be written as `NULL`.
+    ///
+    /// An event that has neither a value nor a default for a column that is not nullable is
+    /// rejected on its own, instead of failing the batch it is part of. A column that the sink
+    /// creates is declared `NOT NULL` when this is `false`.
+    #[serde(default = "crate::serde::default_true")]
+    pub nullable: bool,
+}
+
+struct MappedColumn {
+    name: String,
+    source: ColumnSource,
+    sql_type: Option<String>,
+    default: Option<Value>,
+    nullable: bool,
+}
+
+enum ColumnSource {
+    Field(OwnedValuePath),
+    Expression(Program),
+}
+
+/// The configured columns of a sink, in the order they were configured, followed by the
+/// overflow column if there is one.
+pub struct ColumnMapping {
+    columns: Vec<MappedColumn>,
+    overflow_column: Option<String>,
+    timezone: TimeZone,
+}
+
+impl ColumnMapping {
+    /// Builds the mapping, or `None` if no columns are configured and the columns are instead
+    /// taken from the fields of each batch.
+    ///
+    /// Expressions convert timestamps that do not have a time zone with `timezone`.
+    pub fn new(
+        columns: &IndexMap<String, ColumnConfig>,
+        overflow_column: Option<String>,
+        timezone: TimeZone,
+    ) -> crate::Result<Option<Self>> {
+        if columns.is_empty() {
+            if overflow_column.is_some() {
+                return Err("`overflow_column` requires `columns` to be set.".into());
+            }
+            return Ok(None);
+        }
+        if let Some(overflow_column) = &overflow_column
+            && columns.contains_key(overflow_column)
+        {
+            return Err(format!(
+                "`overflow_column` {overflow_column:?} is also configured in `columns`."
+            )
+            .into());
+        }
+
+        let columns = columns
+            .iter()
+            .map(|(name, column)| {
+                let source = match (&column.field, &column.expression) {
+                    (Some(_), Some(_)) => {
+                        return Err(format!(
+                            "Column {name:?} cannot set both `field` and `expression`."
+                        )
+                        .into());
+                    }
+                    (_, Some(expression)) => ColumnSource::Expression(compile(expression)?),
+                    (Some(field), None) => ColumnSource::Field(field.0.clone()),
+                    (None, None) => ColumnSource::Field(owned_value_path!(name.as_str())),
+                };
+                Ok(MappedColumn {
+                    name: name.clone(),
+                    source,
+                    sql_type: column.sql_type.clone(),
+                    default: column.default.clone(),
+                    nullable: column.nullable,
+                })
+            })
+            .collect::<crate::Result<_>>()?;
+        Ok(Some(Self {
+            columns,
+            overflow_column,
+            timezone,
+        }))
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.columns
+            .iter()
+            .map(|column| column.name.as_str())
+            .chain(self.overflow_column.as_deref())
+    }
+
+    pub fn overflow_column(&self) -> Option<&str> {
+        self.overflow_column.as_deref()
+    }
+
+    /// The fields of `log` that are not written to any column, or `None` if there are none.
+    pub fn overflow(&self, log: &LogEvent) -> Option<Value> {
+        let mut rest = log.value().clone();
+        for column in &self.columns {
+            if let ColumnSource::Field(field) = &column.source {
+                rest.remove(field, true);
+            }
+        }
+        match rest {
+            Value::Object(ref fields) if fields.is_empty() => None,
+            Value::Null => None,
+            rest => Some(rest),
+        }
+    }
+
+    /// The values of `log` for each configured column, in order, falling back to the column
+    /// defaults.
+    ///
+    /// Expressions are evaluated with `runtime`, which is reused for all events of a batch.
+    pub fn values<'a>(
+        &'a self,
+        log: &'a LogEvent,
+        runtime: &mut Runtime,
+    ) -> impl Iterator<Item = Option<Cow<'a, Value>>> {
+        self.columns
+            .iter()
+            .map(move |column| column.value(log, runtime, &self.timezone))
+    }
+
+    /// The first column that is not nullable but that `log` has no value for.
+    ///
+    /// Columns written from an expression are not checked, so that the expression is only
+    /// evaluated once, when the row is built.
+    pub fn missing_required(&self, log: &LogEvent) -> Option<&str> {
+        self.columns
+            .iter()
+            .filter(|column| !column.nullable)
+            .find(|column| match &column.source {
+                ColumnSource::Field(field) => column.field_value(log, field).is_none(),
+                ColumnSource::Expression(_) => false,
+            })
+            .map(|column| column.name.as_str())
+    }
+
+    /// Passes `event` through if it has a value for every column that is not nullable, and
+    /// rejects it otherwise.
+    pub fn check(&self, mut event: Event) -> Option<Event> {
+        let Some(column) = event
+            .maybe_as_log()
+            .and_then(|log| self.missing_required(log))
+        else {
+            return Some(event);
+        };
+
+        emit!(DatabaseMissingColumnValueError { column });
+        event.take_finalizers().update_status(EventStatus::Rejected);
+        None
+    }
+
+    pub fn sql_type(&self, name: &str, dialect: Dialect) -> Option<&str> {
+        if self.overflow_column.as_deref() == Some(name) {
+            return Some(dialect.json_type());
+        }
+        self.columns
+            .iter()
+            .find(|column| column.name == name)
+            .and_then(|column| column.sql_type.as_deref())
+    }
+
+    pub fn is_nullable(&self, name: &str) -> bool {
+        self.columns
+            .iter()
+            .find(|column| column.name == name)
+            .is_none_or(|column| column.nullable)
+    }
+
+    /// The definitions of all columns, for creating the table before anything is written to it.
+    pub fn definitions(&self, dialect: Dialect) -> Vec<ColumnDefinition> {
+        self.columns
+            .iter()
+            .map(|column| ColumnDefinition {
+                name: column.name.clone(),
+                sql_type: column
+                    .sql_type
+                    .clone()
+                    .unwrap_or_else(|| dialect.column_type(None).to_owned()),
+                nullable: column.nullable,
+            })
+            .chain(self.overflow_column.iter().map(|name| ColumnDefinition {
+                name: name.clone(),
+                sql_type: dialect.json_type().to_owned(),
+                nullable: true,
+            }))
+            .collect()
+    }
+}
+
+impl MappedColumn {
+    fn value<'a>(
+        &'a self,
+        log: &'a LogEvent,
+        runtime: &mut Runtime,
+        timezone: &TimeZone,
+    ) -> Option<Cow<'a, Value>> {
+        let program = match &self.source {
+            ColumnSource::Field(field) => return self.field_value(log, field),
+            ColumnSource::Expression(program) => program,
+        };
+
+        let result = runtime.resolve(&mut LogTarget(log), program, timezone);
+        runtime.clear();
+        let value = match result {
+            Ok(value) => Some(value),
+            Err(error) => {
+                emit!(DatabaseColumnExpressionError {
+                    column: &self.name,
+                    error: &error,
+                });
+                None
+            }
+        };
+        value
+            .filter(|value| !value.is_null())
+            .map(Cow::Owned)
+            .or(self.default.as_ref().map(Cow::Borrowed))
+    }
+
+    fn field_value<'a>(
+        &'a self,
+        log: &'a LogEvent,
+        field: &OwnedValuePath,
+    ) -> Option<Cow<'a, Value>> {
+        log.get((PathPrefix::Event, field))
+            .filter(|value| !value.is_null())
+            .or(self.default.as_ref())
+            .map(Cow::Borrowed)
+    }
+}
+
+/// A log event that column expressions are evaluated against, without cloning it.
+///
+/// Expressions are compiled as read-only, so they are rejected before they could write to the
+/// event, and any secrets they set are discarded.
+#[derive(Debug)]
+struct LogTarget<'a>(&'a LogEvent);
+
+const READ_ONLY: &str = "Column expressions cannot modify the event.";
+
+impl Target for LogTarget<'_> {
+    fn target_insert(&mut self, _: &OwnedTargetPath, _: Value) -> Result<(), String> {
+        Err(READ_ONLY.to_owned())
+    }
+
+    fn target_get(&self, target_path: &OwnedTargetPath) -> Result<Option<&Value>, String> {
+        Ok(match target_path.prefix {
+            PathPrefix::Event => self.0.get(&target_path.path),
+            PathPrefix::Metadata => self.0.metadata().value().get(&target_path.path),
+        })
+    }
+
+    fn target_get_mut(&mut self, _: &OwnedTargetPath) -> Result<Option<&mut Value>, String> {
+        Err(READ_ONLY.to_owned())
+    }
+
+    fn target_remove(&mut self, _: &OwnedTargetPath, _: bool) -> Result<Option<Value>, String> {
+        Err(READ_ONLY.to_owned())
+    }
+}
+
+impl SecretTarget for LogTarget<'_> {
+    fn get_secret(&self, key: &str) -> Option<&str> {
+        self.0.metadata().secrets().get_secret(key)
+    }
+
+    fn insert_secret(&mut self, _: &str, _: &str) {}
+
+    fn remove_secret(&mut self, _: &str) {}
+}
+
+/// Compiles the expression of a column, which may read but not modify the event.
+fn compile(expression: &str) -> crate::Result<Program> {
+    let mut config = CompileConfig::default();
+    config.set_read_only();
+    let CompilationResult {
+        program,
+        warnings,
+        config: _,
+    } = compile_vrl(
+        expression,
+        &vector_vrl_functions::all(),
+        &TypeState::default(),
+        config,
+    )
+    .map_err(|diagnostics| format_vrl_diagnostics(expression, diagnostics))?;
+
+    if !warnings.is_empty() {
+        let warnings = format_vrl_diagnostics(expression, warnings);
+        warn!(message = "VRL compilation warning.", %warnings);
+    }
+    Ok(program)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone as _;
+    use vrl::btreemap;
+
+    use super::*;
+
+    fn mapping() -> ColumnMapping {
+        let columns = toml::from_str::<IndexMap<String, ColumnConfig>>(
+            r#"
+            message = {}
+            pod = { field = "kubernetes.pod_name", nullable = false }
+            level = { default = "info" }
+        "#,
+        )
+        .unwrap();
+        ColumnMapping::new(&columns, None, TimeZone::default())
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn empty_mapping_is_none() {
+        assert!(
+            ColumnMapping::new(&IndexMap::new(), None, TimeZone::default())
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            ColumnMapping::new(&IndexMap::new(), Some("extra".into()), TimeZone::default())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn maps_fields_in_configured_order() {
+        let mapping = mapping();
+        assert_eq!(
+            mapping.names().collect::<Vec<_>>(),
+            ["message", "pod", "level"]
+        );
+
+        let log = LogEvent::from(btreemap! {
+            "message" => "hello",
+            "kubernetes" => btreemap! { "pod_name" => "pod-1" },
+            "extra" => true,
+        });
+        assert_eq!(
+            mapping
+                .values(&log, &mut Runtime::default())
+                .collect::<Vec<_>>(),
+            [
+                Some(Cow::Owned(Value::from("hello"))),
+                Some(Cow::Owned(Value::from("pod-1"))),
+                Some(Cow::Owned(Value::from("info"))),
+            ]
+        );
+        assert_eq!(mapping.missing_required(&log), None);
+    }
+
+    #[test]
+    fn finds_missing_required_columns() {
+        let log = LogEvent::from(btreemap! { "message" => "hello" });
+        assert_eq!(mapping().missing_required(&log), Some("pod"));
+    }
+
+    #[test]
+    fn evaluates_expressions() {
+        let columns = toml::from_str::<IndexMap<String, ColumnConfig>>(
+            r#"
+            level = { expression = "upcase(string!(.level))", default = "INFO" }
+            message = {}
+        "#,
+        )
+        .unwrap();
+        let mapping = ColumnMapping::new(&columns, Some("extra".into()), TimeZone::default())
+            .unwrap()
+            .unwrap();
+
+        let log = LogEvent::from(btreemap! { "message" => "hello", "level" => "warn" });
+        assert_eq!(
+            mapping
+                .values(&log, &mut Runtime::default())
+                .collect::<Vec<_>>(),
+            [
+                Some(Cow::Owned(Value::from("WARN"))),
+                Some(Cow::Owned(Value::from("hello"))),
+            ]
+        );
+        // The field that the expression reads is not mapped to a column.
+        assert_eq!(
+            mapping.overflow(&log),
+            Some(Value::from(btreemap! { "level" => "warn" }))
+        );
+
+        let log = LogEvent::from(btreemap! { "message" => "hello" });
+        assert_eq!(
+            mapping.values(&log, &mut Runtime::default()).next(),
+            Some(Some(Cow::Owned(Value::from("INFO"))))
+        );
+    }
+
+    #[test]
+    fn evaluates_expressions_in_timezone() {
+        let columns = toml::from_str::<IndexMap<String, ColumnConfig>>(
+            r#"at = { expression = "parse_timestamp!(string!(.at), \"%F %T\")" }"#,
+        )
+        .unwrap();
+        let mapping = ColumnMapping::new(
+            &columns,
+            None,
+            TimeZone::parse("America/Los_Angeles").unwrap(),
+        )
+        .unwrap()
+        .unwrap();
+
+        let log = LogEvent::from(btreemap! { "at" => "2025-01-01 00:00:00" });
+        let timestamp = chrono::Utc.with_ymd_and_hms(2025, 1, 1, 8, 0, 0).unwrap();
+        assert_eq!(
+            mapping.values(&log, &mut Runtime::default()).next(),
+            Some(Some(Cow::Owned(Value::from(timestamp))))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        let columns = toml::from_str::<IndexMap<String, ColumnConfig>>(
+            r#"level = { expression = ".level = 1" }"#,
+        )
+        .unwrap();
+        assert!(ColumnMapping::new(&columns, None, TimeZone::default()).is_err());
+
+        let columns = toml::from_str::<IndexMap<String, ColumnConfig>>(
+            r#"level = { field = "level", expression = ".level" }"#,
+        )
+        .unwrap();
+        assert!(ColumnMapping::new(&columns, None, TimeZone::default()).is_err());
+    }
+
+    #[test]
+    fn collects_unmapped_fields() {
+        let columns = mapping().columns;
+        let mapping = ColumnMapping {
+            columns,
+            overflow_column: Some("extra".into()),
+            timezone: TimeZone::default(),
+        };
+        assert_eq!(
+            mapping.names().collect::<Vec<_>>(),
+            ["message", "pod", "level", "extra"]
+        );
+
+        let log = LogEvent::from(btreemap! {
+            "message" => "hello",
+            "kubernetes" => btreemap! { "pod_name" => "pod-1", "namespace" => "default" },
+            "status" => 200,
+        });
+        assert_eq!(
+            mapping.overflow(&log),
+            Some(Value::from(btreemap! {
+                "kubernetes" => btreemap! { "namespace" => "default" },
+                "status" => 200,
+            }))
+        );
+
+        let log = LogEvent::from(btreemap! {
+            "message" => "hello",
+            "kubernetes" => btreemap! { "pod_name" => "pod-1" },
+        });
+        assert_eq!(mapping.overflow(&log), None);
+    }
+}
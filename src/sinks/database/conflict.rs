@@ -0,0 +1,171 @@
+use vector_lib::configurable::configurable_component;
+
+use super::Dialect;
+
+/// How rows that conflict with an existing row, on a primary key or unique index, are
+/// handled.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// A conflicting row fails the insert, and with it the whole batch.
+    #[default]
+    Error,
+
+    /// A conflicting row is skipped, and the existing row is kept as is.
+    Ignore,
+
+    /// A conflicting row updates the existing row.
+    ///
+    /// Every column of the batch that is not a key column is overwritten, including columns
+    /// that the event does not set, which are written as they would be on insert.
+    Upsert,
+}
+
+/// The conflict handling of a sink, checked against its key columns.
+#[derive(Clone, Debug, Default)]
+pub struct OnConflict {
+    policy: ConflictPolicy,
+    key_columns: Vec<String>,
+}
+
+impl OnConflict {
+    pub fn new(policy: ConflictPolicy, key_columns: Vec<String>) -> crate::Result<Self> {
+        if policy != ConflictPolicy::Error && key_columns.is_empty() {
+            return Err("`key_columns` must be set when `conflict` is not `error`.".into());
+        }
+        Ok(Self {
+            policy,
+            key_columns,
+        })
+    }
+
+    /// Renders the clause appended to an `INSERT` of `columns`, if any.
+    pub fn clause(&self, dialect: Dialect, columns: &[String]) -> Option<String> {
+        let updated = match self.policy {
+            ConflictPolicy::Error => return None,
+            ConflictPolicy::Ignore => Vec::new(),
+            ConflictPolicy::Upsert => columns
+                .iter()
+                .filter(|column| !self.key_columns.contains(column))
+                .map(|column| dialect.quote_identifier(column))
+                .collect(),
+        };
+
+        Some(match dialect {
+            // MySQL has no "do nothing"; assigning a key column to itself is the idiomatic no-op.
+            // Updates read the inserted values through a row alias, as `VALUES()` is deprecated.
+            #[cfg(feature = "sinks-mysql")]
+            Dialect::MySql => {
+                if updated.is_empty() {
+                    let key = dialect.quote_identifier(&self.key_columns[0]);
+                    format!(" ON DUPLICATE KEY UPDATE {key} = {key}")
+                } else {
+                    let assignments = updated
+                        .iter()
+                        .map(|column| format!("{column} = new.{column}"))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    format!(" AS new ON DUPLICATE KEY UPDATE {assignments}")
+                }
+            }
+            #[cfg(feature = "sinks-postgres")]
+            Dialect::Postgres => self.on_conflict(dialect, &updated, "EXCLUDED"),
+            #[cfg(feature = "sinks-sqlite")]
+            Dialect::Sqlite => self.on_conflict(dialect, &updated, "excluded"),
+        })
+    }
+
+    /// Renders an `ON CONFLICT` clause on the key columns, updating the `updated` columns from
+    /// the row that was not inserted, which is named `excluded`.
+    #[cfg(any(feature = "sinks-postgres", feature = "sinks-sqlite"))]
+    fn on_conflict(&self, dialect: Dialect, updated: &[String], excluded: &str) -> String {
+        let target = self
+            .key_columns
+            .iter()
+            .map(|column| dialect.quote_identifier(column))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let action = if updated.is_empty() {
+            "NOTHING".to_owned()
+        } else {
+            let assignments = updated
+                .iter()
+                .map(|column| format!("{column} = {excluded}.{column}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("UPDATE SET {assignments}")
+        };
+        format!(" ON CONFLICT ({target}) DO {action}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns() -> Vec<String> {
+        vec!["id".into(), "message".into()]
+    }
+
+    #[test]
+    fn key_columns_are_required() {
+        assert!(OnConflict::new(ConflictPolicy::Error, Vec::new()).is_ok());
+        assert!(OnConflict::new(ConflictPolicy::Ignore, Vec::new()).is_err());
+        assert!(OnConflict::new(ConflictPolicy::Upsert, Vec::new()).is_err());
+    }
+
+    #[cfg(feature = "sinks-mysql")]
+    #[test]
+    fn renders_mysql_clauses() {
+        let ignore = OnConflict::new(ConflictPolicy::Ignore, vec!["id".into()]).unwrap();
+        assert_eq!(
+            ignore.clause(Dialect::MySql, &columns()).unwrap(),
+            " ON DUPLICATE KEY UPDATE `id` = `id`"
+        );
+
+        let upsert = OnConflict::new(ConflictPolicy::Upsert, vec!["id".into()]).unwrap();
+        assert_eq!(
+            upsert.clause(Dialect::MySql, &columns()).unwrap(),
+            " AS new ON DUPLICATE KEY UPDATE `message` = new.`message`"
+        );
+    }
+
+    #[cfg(feature = "sinks-postgres")]
+    #[test]
+    fn renders_postgres_clauses() {
+        let ignore = OnConflict::new(ConflictPolicy::Ignore, vec!["id".into()]).unwrap();
+        assert_eq!(
+            ignore.clause(Dialect::Postgres, &columns()).unwrap(),
+            r#" ON CONFLICT ("id") DO NOTHING"#
+        );
+
+        let upsert = OnConflict::new(ConflictPolicy::Upsert, vec!["id".into()]).unwrap();
+        assert_eq!(
+            upsert.clause(Dialect::Postgres, &columns()).unwrap(),
+            r#" ON CONFLICT ("id") DO UPDATE SET "message" = EXCLUDED."message""#
+        );
+    }
+
+    #[cfg(feature = "sinks-sqlite")]
+    #[test]
+    fn renders_sqlite_clauses() {
+        let ignore = OnConflict::new(ConflictPolicy::Ignore, vec!["id".into()]).unwrap();
+        assert_eq!(
+            ignore.clause(Dialect::Sqlite, &columns()).unwrap(),
+            r#" ON CONFLICT ("id") DO NOTHING"#
+        );
+
+        let upsert = OnConflict::new(ConflictPolicy::Upsert, vec!["id".into()]).unwrap();
+        assert_eq!(
+            upsert.clause(Dialect::Sqlite, &columns()).unwrap(),
+            r#" ON CONFLICT ("id") DO UPDATE SET "message" = excluded."message""#
+        );
+
+        // With only key columns in the batch there is nothing left to update.
+        assert_eq!(
+            upsert.clause(Dialect::Sqlite, &["id".into()]).unwrap(),
+            r#" ON CONFLICT ("id") DO NOTHING"#
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use chrono::{DateTime, Utc};
+use vector_lib::event::{LogEvent, Value};
+
+use super::{ColumnDefinition, Dialect, InsertStatement, OnConflict, Rows, SqlValue};
+
+/// The columns of the dead-letter table: the table that the row failed to insert into, the
+/// error, the row itself as a JSON object of its columns, and the time that it failed.
+pub fn dead_letter_columns(dialect: Dialect) -> Vec<ColumnDefinition> {
+    let text = dialect.column_type(Some(&SqlValue::Text(String::new())));
+    let timestamp = dialect.column_type(Some(&SqlValue::Timestamp(DateTime::UNIX_EPOCH)));
+    [
+        ("error", text),
+        ("row", dialect.json_type()),
+        ("table", text),
+        ("timestamp", timestamp),
+    ]
+    .into_iter()
+    .map(|(name, sql_type)| ColumnDefinition {
+        name: name.to_owned(),
+        sql_type: sql_type.to_owned(),
+        nullable: false,
+    })
+    .collect()
+}
+
+/// The `INSERT` into `dead_letter_table` of `row`, a single row that failed to insert into
+/// `table` with `error`.
+pub fn dead_letter_insert(
+    dialect: Dialect,
+    dead_letter_table: &str,
+    table: &str,
+    row: &Rows,
+    error: &impl std::fmt::Display,
+) -> InsertStatement {
+    let columns = row
+        .first_values()
+        .filter_map(|(column, value)| Some((column.to_owned(), value?.clone().into())))
+        .collect::<serde_json::Map<_, _>>();
+
+    let mut log = LogEvent::default();
+    log.insert("table", table.to_owned());
+    log.insert("error", error.to_string());
+    log.insert("row", Value::from(serde_json::Value::Object(columns)));
+    log.insert("timestamp", Utc::now());
+    InsertStatement::new(
+        dialect,
+        dead_letter_table,
+        Rows::from_logs([&log]),
+        &OnConflict::default(),
+    )
+}
+
+#[cfg(all(test, feature = "sinks-sqlite"))]
+mod tests {
+    use vrl::btreemap;
+
+    use super::*;
+
+    #[test]
+    fn writes_failed_rows() {
+        let log = LogEvent::from(btreemap! {
+            "id" => 3,
+            "tags" => btreemap! { "env" => "prod" },
+        });
+        let statement = dead_letter_insert(
+            Dialect::Sqlite,
+            "failed",
+            "logs",
+            &Rows::from_logs([&log]),
+            &"CHECK constraint failed",
+        );
+
+        assert_eq!(
+            statement.sql,
+            r#"INSERT INTO "failed" ("error", "row", "table", "timestamp") VALUES (?, ?, ?, ?)"#
+        );
+        assert_eq!(
+            statement.params[..3],
+            [
+                SqlValue::Text("CHECK constraint failed".into()),
+                SqlValue::Json(r#"{"id":3,"tags":{"env":"prod"}}"#.into()),
+                SqlValue::Text("logs".into()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use std::collections::HashSet;
+
+use super::{ColumnMapping, Dialect, SchemaManagement};
+
+/// Checks that `table`, with the columns in `existing`, can be written to as configured.
+///
+/// A table or column that is missing is only an error if the sink will not create it.
+pub fn check_table(
+    table: &str,
+    management: SchemaManagement,
+    existing: Option<&HashSet<String>>,
+    mapping: Option<&ColumnMapping>,
+) -> crate::Result<()> {
+    let Some(existing) = existing else {
+        if management == SchemaManagement::None {
+            return Err(format!("Table {table:?} does not exist.").into());
+        }
+        return Ok(());
+    };
+
+    if management != SchemaManagement::CreateAndAlter
+        && let Some(missing) = mapping
+            .into_iter()
+            .flat_map(ColumnMapping::names)
+            .find(|column| !existing.contains(*column))
+    {
+        return Err(format!("Table {table:?} has no column {missing:?}.").into());
+    }
+    Ok(())
+}
+
+/// An `INSERT` into `table` that writes no rows, to check that the sink may insert into it.
+///
+/// The columns written are those in `mapping`, or every column of the table.
+pub fn insert_probe(dialect: Dialect, table: &str, mapping: Option<&ColumnMapping>) -> String {
+    let table = dialect.quote_qualified(table);
+    match mapping {
+        Some(mapping) => {
+            let columns = mapping
+                .names()
+                .map(|column| dialect.quote_identifier(column))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("INSERT INTO {table} ({columns}) SELECT {columns} FROM {table} WHERE 1 = 0")
+        }
+        None => format!("INSERT INTO {table} SELECT * FROM {table} WHERE 1 = 0"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexMap;
+    use vector_lib::TimeZone;
+
+    use super::*;
+    use crate::sinks::database::ColumnConfig;
+
+    fn mapping() -> ColumnMapping {
+        let columns =
+            toml::from_str::<IndexMap<String, ColumnConfig>>("id = {}\nmessage = {}").unwrap();
+        ColumnMapping::new(&columns, None, TimeZone::default())
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn missing_tables_fail_unless_created() {
+        assert!(check_table("logs", SchemaManagement::None, None, None).is_err());
+        assert!(check_table("logs", SchemaManagement::Create, None, None).is_ok());
+    }
+
+    #[test]
+    fn missing_columns_fail_unless_added() {
+        let existing = HashSet::from(["id".to_owned()]);
+        let mapping = mapping();
+        for management in [SchemaManagement::None, SchemaManagement::Create] {
+            assert!(check_table("logs", management, Some(&existing), Some(&mapping)).is_err());
+            assert!(check_table("logs", management, Some(&existing), None).is_ok());
+        }
+        assert!(
+            check_table(
+                "logs",
+                SchemaManagement::CreateAndAlter,
+                Some(&existing),
+                Some(&mapping)
+            )
+            .is_ok()
+        );
+    }
+
+    #[cfg(feature = "sinks-sqlite")]
+    #[test]
+    fn renders_insert_probes() {
+        assert_eq!(
+            insert_probe(Dialect::Sqlite, "logs", Some(&mapping())),
+            r#"INSERT INTO "logs" ("id", "message") SELECT "id", "message" FROM "logs" WHERE 1 = 0"#
+        );
+        assert_eq!(
+            insert_probe(Dialect::Sqlite, "logs", None),
+            r#"INSERT INTO "logs" SELECT * FROM "logs" WHERE 1 = 0"#
+        );
+    }
+}
@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+
+use indexmap::IndexMap;
+use vector_lib::{
+    configurable::configurable_component,
+    event::{Event, LogEvent, Metric, MetricValue, Value},
+    lookup::event_path,
+};
+use vrl::value::ObjectMap;
+
+use crate::internal_events::DatabaseMetricRejectedError;
+
+/// How metric events are written as rows.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct MetricsConfig {
+    /// The layout of the rows.
+    #[configurable(derived)]
+    #[serde(default)]
+    pub layout: MetricLayout,
+
+    /// The value columns of the `wide` layout, keyed by column name, each with the name of the
+    /// metric whose value is written to it.
+    #[configurable(metadata(
+        docs::additional_props_description = "The name of the metric written to the column."
+    ))]
+    #[serde(default)]
+    pub columns: IndexMap<String, String>,
+}
+
+/// The layout of the rows that metrics are written as.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricLayout {
+    /// Each metric is a row of `name`, `namespace`, `tags`, `value`, and `timestamp` columns.
+    #[default]
+    Narrow,
+
+    /// Metrics with the same tags and timestamp are merged into a single row of `tags` and
+    /// `timestamp` columns, with the value of each metric in its configured column.
+    Wide,
+}
+
+/// A batch of metrics, converted to the logs that its rows are built from.
+pub struct MetricRows {
+    pub logs: Vec<LogEvent>,
+    /// The indexes of the events that each log is built from.
+    sources: Vec<Vec<usize>>,
+    /// The indexes of the events that cannot be written.
+    rejected: Vec<usize>,
+}
+
+impl MetricRows {
+    /// The indexes of the events that were rejected, either when converting them or because
+    /// they are part of one of the rejected `rows`.
+    pub fn rejected_events(&self, rows: Vec<usize>) -> Vec<usize> {
+        let rejected_rows = rows
+            .into_iter()
+            .flat_map(|row| self.sources[row].iter().copied());
+        self.rejected.iter().copied().chain(rejected_rows).collect()
+    }
+}
+
+impl MetricsConfig {
+    pub fn validate(&self) -> crate::Result<()> {
+        if self.layout == MetricLayout::Wide && self.columns.is_empty() {
+            return Err("`metrics.columns` must be set for the `wide` layout.".into());
+        }
+        Ok(())
+    }
+
+    /// Converts the metrics in `events` into logs, rejecting the metrics that have no single
+    /// value, or that are not mapped to a column of the `wide` layout.
+    pub fn rows(&self, events: &[Event]) -> MetricRows {
+        let columns = self
+            .columns
+            .iter()
+            .map(|(column, metric)| (metric.as_str(), column.as_str()))
+            .collect::<HashMap<_, _>>();
+
+        let mut rows = MetricRows {
+            logs: Vec::new(),
+            sources: Vec::new(),
+            rejected: Vec::new(),
+        };
+        let mut merged = HashMap::new();
+        for (index, metric) in events
+            .iter()
+            .enumerate()
+            .filter_map(|(index, event)| event.maybe_as_metric().map(|metric| (index, metric)))
+        {
+            let Some(value) = scalar_value(metric.value()) else {
+                emit!(DatabaseMetricRejectedError {
+                    metric: metric.name(),
+                    reason: "Metric has no single value to write.",
+                });
+                rows.rejected.push(index);
+                continue;
+            };
+
+            match self.layout {
+                MetricLayout::Narrow => {
+                    let mut log = base_log(metric);
+                    log.insert("name", metric.name().to_owned());
+                    if let Some(namespace) = metric.namespace() {
+                        log.insert("namespace", namespace.to_owned());
+                    }
+                    log.insert("value", value);
+                    rows.logs.push(log);
+                    rows.sources.push(vec![index]);
+                }
+                MetricLayout::Wide => {
+                    let Some(column) = columns.get(metric.name()) else {
+                        emit!(DatabaseMetricRejectedError {
+                            metric: metric.name(),
+                            reason: "Metric is not mapped to a column.",
+                        });
+                        rows.rejected.push(index);
+                        continue;
+                    };
+                    let row = *merged
+                        .entry((metric.timestamp(), metric.tags()))
+                        .or_insert_with(|| {
+                            rows.logs.push(base_log(metric));
+                            rows.sources.push(Vec::new());
+                            rows.logs.len() - 1
+                        });
+                    rows.logs[row].insert(event_path!(*column), value);
+                    rows.sources[row].push(index);
+                }
+            }
+        }
+        rows
+    }
+}
+
+/// A log with the tags and timestamp of `metric`.
+fn base_log(metric: &Metric) -> LogEvent {
+    let tags = metric
+        .tags()
+        .into_iter()
+        .flat_map(|tags| tags.iter_single())
+        .map(|(key, value)| (key.into(), Value::from(value)))
+        .collect::<ObjectMap>();
+    let mut log = LogEvent::default();
+    log.insert("tags", tags);
+    if let Some(timestamp) = metric.timestamp() {
+        log.insert("timestamp", timestamp);
+    }
+    log
+}
+
+fn scalar_value(value: &MetricValue) -> Option<f64> {
+    match value {
+        MetricValue::Counter { value } | MetricValue::Gauge { value } => Some(*value),
+        MetricValue::Set { values } => Some(values.len() as f64),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_lib::{
+        event::{MetricKind, StatisticKind},
+        metric_tags,
+    };
+
+    use super::*;
+
+    fn gauge(name: &str, host: &str, value: f64) -> Event {
+        Metric::new(name, MetricKind::Absolute, MetricValue::Gauge { value })
+            .with_tags(Some(metric_tags!("host" => host)))
+            .into()
+    }
+
+    #[test]
+    fn writes_narrow_rows() {
+        let events = [
+            gauge("cpu", "a", 1.0),
+            Metric::new(
+                "latency",
+                MetricKind::Incremental,
+                MetricValue::Distribution {
+                    samples: Vec::new(),
+                    statistic: StatisticKind::Histogram,
+                },
+            )
+            .into(),
+        ];
+        let rows = MetricsConfig::default().rows(&events);
+
+        assert_eq!(rows.logs.len(), 1);
+        assert_eq!(rows.logs[0]["name"], "cpu".into());
+        assert_eq!(rows.logs[0]["value"], 1.0.into());
+        assert_eq!(rows.logs[0]["tags.host"], "a".into());
+        assert_eq!(rows.sources, [vec![0]]);
+        assert_eq!(rows.rejected, [1]);
+    }
+
+    #[test]
+    fn merges_wide_rows() {
+        let config = MetricsConfig {
+            layout: MetricLayout::Wide,
+            columns: IndexMap::from([
+                ("cpu_usage".to_owned(), "cpu".to_owned()),
+                ("memory_usage".to_owned(), "memory".to_owned()),
+            ]),
+        };
+        let events = [
+            gauge("cpu", "a", 1.0),
+            gauge("memory", "a", 2.0),
+            gauge("cpu", "b", 3.0),
+            gauge("disk", "a", 4.0),
+        ];
+        let rows = config.rows(&events);
+
+        assert_eq!(rows.logs.len(), 2);
+        assert_eq!(rows.logs[0]["cpu_usage"], 1.0.into());
+        assert_eq!(rows.logs[0]["memory_usage"], 2.0.into());
+        assert_eq!(rows.logs[1]["tags.host"], "b".into());
+        assert_eq!(rows.sources, [vec![0, 1], vec![2]]);
+        assert_eq!(rows.rejected_events(vec![0]), [3, 0, 1]);
+    }
+}
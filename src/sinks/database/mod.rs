@@ -0,0 +1,72 @@
+//! Sinks that write events as rows into SQL databases.
+//!
+//! Converting events into rows, rendering the `INSERT` statement, and writing the rows of a
+//! batch under a transaction are shared between the backends in this module, generic over the
+//! `sqlx` database; each backend only manages its connections and tables. The `postgres` sink
+//! only shares the conflict handling, checks, and retries, so most of the module is unused when
+//! it is the only database sink built.
+#![cfg_attr(
+    not(any(feature = "sinks-mysql", feature = "sinks-sqlite")),
+    allow(dead_code, unused_imports)
+)]
+
+mod columns;
+mod conflict;
+mod dead_letter;
+mod healthcheck;
+mod metrics;
+#[cfg(feature = "sinks-mysql")]
+pub mod mysql;
+mod partial_failure;
+mod partitioner;
+#[cfg(feature = "sinks-mysql")]
+mod procedure;
+mod retry;
+mod rows;
+mod schema;
+#[cfg(any(feature = "sinks-mysql", feature = "sinks-sqlite"))]
+mod service;
+#[cfg(feature = "sinks-sqlite")]
+pub mod sqlite;
+mod time_partition;
+
+use crate::sinks::util::SinkBatchSettings;
+
+#[cfg(feature = "sinks-mysql")]
+pub use self::procedure::{ProcedureConfig, ProcedureMode};
+pub use self::{
+    columns::ColumnConfig,
+    conflict::ConflictPolicy,
+    metrics::{MetricLayout, MetricsConfig},
+    partial_failure::PartialFailure,
+    schema::SchemaManagement,
+    time_partition::{PartitionInterval, TimePartitionConfig},
+};
+pub(crate) use self::{
+    columns::ColumnMapping,
+    conflict::OnConflict,
+    dead_letter::{dead_letter_columns, dead_letter_insert},
+    healthcheck::{check_table, insert_probe},
+    partitioner::TablePartitioner,
+    retry::is_retriable,
+    rows::{Dialect, InsertStatement, Rows, SqlValue},
+    schema::{ColumnDefinition, migration, split_qualified},
+};
+#[cfg(any(feature = "sinks-mysql", feature = "sinks-sqlite"))]
+pub(crate) use self::{
+    metrics::MetricRows,
+    service::{DatabaseRequest, DatabaseResponse, RowWriter},
+};
+
+/// Default batch settings for the database sinks.
+///
+/// Batches are bounded by event count as well as size, since every event is a row of a single
+/// `INSERT` and statements with many rows are slow to parse and plan.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DatabaseDefaultBatchSettings;
+
+impl SinkBatchSettings for DatabaseDefaultBatchSettings {
+    const MAX_EVENTS: Option<usize> = Some(1000);
+    const MAX_BYTES: Option<usize> = Some(10_000_000);
+    const TIMEOUT_SECS: f64 = 1.0;
+}
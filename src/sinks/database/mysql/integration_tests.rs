@@ -0,0 +1,254 @@
+use std::future::ready;
+
+use futures::stream;
+use sqlx::{Connection, MySqlConnection};
+use vector_lib::event::{BatchNotifier, BatchStatus, BatchStatusReceiver, Event, LogEvent};
+
+use crate::{
+    config::{SinkConfig, SinkContext},
+    sinks::{
+        database::{ConflictPolicy, ProcedureConfig, ProcedureMode, mysql::MySqlConfig},
+        util::test::load_sink,
+    },
+    test_util::{
+        components::{
+            COMPONENT_ERROR_TAGS, run_and_assert_sink_compliance, run_and_assert_sink_error,
+        },
+        integration::mysql::mysql_url,
+        random_table_name, trace_init,
+    },
+};
+
+const MYSQL_SINK_TAGS: [&str; 2] = ["endpoint", "protocol"];
+
+fn create_event(id: i64) -> Event {
+    let mut event = LogEvent::from("raw log line");
+    event.insert("id", id);
+    event.insert("host", "example.com");
+    event.into()
+}
+
+fn create_event_with_notifier(id: i64) -> (Event, BatchStatusReceiver) {
+    let (batch, receiver) = BatchNotifier::new_with_receiver();
+    let event = create_event(id).with_batch_notifier(&batch);
+    (event, receiver)
+}
+
+fn create_events(count: usize) -> (Vec<Event>, BatchStatusReceiver) {
+    let mut events = (0..count as i64).map(create_event).collect::<Vec<_>>();
+    let receiver = BatchNotifier::apply_to(&mut events);
+    (events, receiver)
+}
+
+async fn prepare_config() -> (MySqlConfig, String, MySqlConnection) {
+    let table = random_table_name();
+    let endpoint = mysql_url();
+    let config_str = format!(
+        r#"
+            endpoint = "{endpoint}"
+            table = "{table}"
+        "#,
+    );
+    let (config, _) = load_sink::<MySqlConfig>(&config_str).unwrap();
+
+    let connection = MySqlConnection::connect(endpoint.as_str())
+        .await
+        .expect("Failed to connect to MySQL");
+
+    (config, table, connection)
+}
+
+async fn create_table(connection: &mut MySqlConnection, table: &str, columns: &str) {
+    sqlx::query(&format!("CREATE TABLE {table} ({columns})"))
+        .execute(connection)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn healthcheck_passes() {
+    trace_init();
+    let (config, table, mut connection) = prepare_config().await;
+    create_table(&mut connection, &table, "id BIGINT, message TEXT").await;
+    let (_sink, healthcheck) = config
+        .build(SinkContext::default())
+        .await
+        .expect("sink should build successfully");
+    assert!(healthcheck.await.is_ok());
+}
+
+#[tokio::test]
+async fn healthcheck_fails_missing_table() {
+    trace_init();
+    let (config, _table, _connection) = prepare_config().await;
+    let (_sink, healthcheck) = config
+        .build(SinkContext::default())
+        .await
+        .expect("sink should build successfully");
+    assert!(healthcheck.await.is_err());
+}
+
+#[tokio::test]
+async fn healthcheck_fails_unknown_host() {
+    trace_init();
+
+    let config_str = r#"
+            endpoint = "mysql://unknown_host"
+            table = "logs"
+        "#;
+    let (config, cx) = load_sink::<MySqlConfig>(config_str).unwrap();
+
+    let (_sink, healthcheck) = config
+        .build(cx)
+        .await
+        .expect("sink should build successfully");
+    assert!(healthcheck.await.is_err());
+}
+
+#[tokio::test]
+async fn insert_multiple_events() {
+    trace_init();
+
+    let (config, table, mut connection) = prepare_config().await;
+    let (sink, _hc) = config.build(SinkContext::default()).await.unwrap();
+    create_table(
+        &mut connection,
+        &table,
+        "id BIGINT, host TEXT, timestamp DATETIME(6), message TEXT",
+    )
+    .await;
+
+    let (input_events, mut receiver) = create_events(100);
+    run_and_assert_sink_compliance(sink, stream::iter(input_events), &MYSQL_SINK_TAGS).await;
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    let rows: Vec<(i64, String, String)> = sqlx::query_as(&format!(
+        "SELECT id, host, message FROM {table} ORDER BY id"
+    ))
+    .fetch_all(&mut connection)
+    .await
+    .unwrap();
+    assert_eq!(rows.len(), 100);
+    for (expected_id, (id, host, message)) in rows.into_iter().enumerate() {
+        assert_eq!(id, expected_id as i64);
+        assert_eq!(host, "example.com");
+        assert_eq!(message, "raw log line");
+    }
+}
+
+#[tokio::test]
+async fn missing_fields_use_column_default() {
+    trace_init();
+
+    let (config, table, mut connection) = prepare_config().await;
+    let (sink, _hc) = config.build(SinkContext::default()).await.unwrap();
+    create_table(
+        &mut connection,
+        &table,
+        "id BIGINT, host TEXT, timestamp DATETIME(6), message TEXT, level VARCHAR(16) DEFAULT 'info'",
+    )
+    .await;
+
+    let (input_event, mut receiver) = create_event_with_notifier(0);
+    run_and_assert_sink_compliance(sink, stream::once(ready(input_event)), &MYSQL_SINK_TAGS).await;
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    let (level,): (String,) = sqlx::query_as(&format!("SELECT level FROM {table}"))
+        .fetch_one(&mut connection)
+        .await
+        .unwrap();
+    assert_eq!(level, "info");
+}
+
+#[tokio::test]
+async fn insertion_fails_unknown_column() {
+    trace_init();
+
+    let (config, table, mut connection) = prepare_config().await;
+    let (sink, _hc) = config.build(SinkContext::default()).await.unwrap();
+    create_table(&mut connection, &table, "message TEXT").await;
+
+    let (input_event, mut receiver) = create_event_with_notifier(0);
+    run_and_assert_sink_error(
+        sink,
+        stream::once(ready(input_event)),
+        &COMPONENT_ERROR_TAGS,
+    )
+    .await;
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Rejected));
+}
+
+#[tokio::test]
+async fn upsert_updates_existing_rows() {
+    trace_init();
+
+    let (mut config, table, mut connection) = prepare_config().await;
+    config.conflict = ConflictPolicy::Upsert;
+    config.key_columns = vec!["id".into()];
+    create_table(
+        &mut connection,
+        &table,
+        "id BIGINT PRIMARY KEY, host TEXT, message TEXT, timestamp DATETIME(6)",
+    )
+    .await;
+
+    for message in ["first", "second"] {
+        let (sink, _hc) = config.build(SinkContext::default()).await.unwrap();
+        let mut event = LogEvent::from(message);
+        event.insert("id", 1);
+        event.insert("host", "example.com");
+        run_and_assert_sink_compliance(
+            sink,
+            stream::once(ready(Event::from(event))),
+            &MYSQL_SINK_TAGS,
+        )
+        .await;
+    }
+
+    let messages: Vec<String> = sqlx::query_scalar(&format!("SELECT message FROM {table}"))
+        .fetch_all(&mut connection)
+        .await
+        .unwrap();
+    assert_eq!(messages, ["second"]);
+}
+
+#[tokio::test]
+async fn procedure_writes_rows() {
+    trace_init();
+
+    let (mut config, table, mut connection) = prepare_config().await;
+    create_table(&mut connection, &table, "id BIGINT, message TEXT").await;
+    let procedure = format!("{table}_insert");
+    sqlx::raw_sql(&format!(
+        "CREATE PROCEDURE {procedure}(IN row_id BIGINT, IN row_message TEXT) \
+         INSERT INTO {table} (id, message) VALUES (row_id, UPPER(row_message))"
+    ))
+    .execute(&mut connection)
+    .await
+    .unwrap();
+
+    config.columns = toml::from_str("id = {}\nmessage = {}").unwrap();
+    config.procedure = Some(ProcedureConfig {
+        name: procedure,
+        mode: ProcedureMode::Row,
+    });
+    let (sink, healthcheck) = config.build(SinkContext::default()).await.unwrap();
+    healthcheck.await.unwrap();
+
+    let (events, mut receiver) = create_events(2);
+    run_and_assert_sink_compliance(sink, stream::iter(events), &MYSQL_SINK_TAGS).await;
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    let rows: Vec<(i64, String)> =
+        sqlx::query_as(&format!("SELECT id, message FROM {table} ORDER BY id"))
+            .fetch_all(&mut connection)
+            .await
+            .unwrap();
+    assert_eq!(
+        rows,
+        [
+            (0, "RAW LOG LINE".to_owned()),
+            (1, "RAW LOG LINE".to_owned())
+        ]
+    );
+}
@@ -0,0 +1,12 @@
+//! The MySQL [`vector_lib::sink::VectorSink`].
+//!
+//! Events are inserted as rows into a single table, one multi-row `INSERT` per batch, with the
+//! top-level fields of each event mapped to the columns of the same name.
+
+mod config;
+#[cfg(all(test, feature = "mysql-integration-tests"))]
+mod integration_tests;
+mod service;
+mod sink;
+
+pub use self::config::MySqlConfig;
@@ -0,0 +1,435 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use futures::future::BoxFuture;
+use snafu::{ResultExt, Snafu};
+use sqlx::{MySql, Pool, mysql::MySqlDatabaseError};
+use tokio::sync::Mutex;
+use tower::Service;
+
+use crate::{
+    internal_events::EndpointBytesSent,
+    sinks::{
+        database::{
+            ColumnDefinition, ColumnMapping, DatabaseRequest, DatabaseResponse, Dialect,
+            InsertStatement, MetricsConfig, OnConflict, PartialFailure, ProcedureConfig, RowWriter,
+            Rows, SchemaManagement, TimePartitionConfig, check_table, dead_letter_columns,
+            insert_probe, is_retriable, migration, split_qualified,
+        },
+        prelude::RetryLogic,
+    },
+    template::Template,
+};
+
+const MYSQL_PROTOCOL: &str = "mysql";
+
+/// The error returned when the server rejects the user name or password.
+const ER_ACCESS_DENIED_ERROR: u16 = 1045;
+
+#[derive(Clone)]
+pub struct MySqlRetryLogic;
+
+impl RetryLogic for MySqlRetryLogic {
+    type Error = MySqlServiceError;
+    type Request = DatabaseRequest;
+    type Response = DatabaseResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        match error {
+            MySqlServiceError::MySql {
+                source: mysql_error,
+            } => is_retriable(Dialect::MySql, mysql_error),
+            MySqlServiceError::CredentialsChanged { .. } => true,
+        }
+    }
+}
+
+/// A file holding the password of the MySQL user.
+pub struct PasswordFile {
+    path: PathBuf,
+    state: std::sync::Mutex<PasswordState>,
+}
+
+struct PasswordState {
+    /// The password last read from the file.
+    password: String,
+    /// Incremented each time the password changes.
+    generation: u64,
+}
+
+impl PasswordFile {
+    pub async fn new(path: PathBuf) -> io::Result<Self> {
+        let password = read_password(&path).await?;
+        Ok(Self {
+            path,
+            state: std::sync::Mutex::new(PasswordState {
+                password,
+                generation: 0,
+            }),
+        })
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, PasswordState> {
+        self.state.lock().expect("password lock poisoned")
+    }
+
+    pub fn password(&self) -> String {
+        self.state().password.clone()
+    }
+
+    /// The generation of the current password, compared by requests that failed with the password
+    /// they started with.
+    fn generation(&self) -> u64 {
+        self.state().generation
+    }
+
+    /// Reads the file again, passing the password to `update` if it changed, and returns the
+    /// generation of the current password.
+    async fn reload(&self, update: impl FnOnce(&str)) -> Option<u64> {
+        let password = match read_password(&self.path).await {
+            Ok(password) => password,
+            Err(error) => {
+                warn!(
+                    message = "Failed to read MySQL password file.",
+                    path = ?self.path,
+                    %error,
+                );
+                return None;
+            }
+        };
+        let mut state = self.state();
+        if state.password != password {
+            update(&password);
+            state.password = password;
+            state.generation += 1;
+        }
+        Some(state.generation)
+    }
+}
+
+async fn read_password(path: &Path) -> io::Result<String> {
+    let password = tokio::fs::read_to_string(path).await?;
+    Ok(password.trim_end_matches(['\r', '\n']).to_owned())
+}
+
+/// Whether the server rejected the credentials of a new connection.
+fn is_access_denied(error: &sqlx::Error) -> bool {
+    error
+        .as_database_error()
+        .and_then(|error| error.try_downcast_ref::<MySqlDatabaseError>())
+        .is_some_and(|error| error.number() == ER_ACCESS_DENIED_ERROR)
+}
+
+pub struct MySqlSettings {
+    pub on_conflict: OnConflict,
+    pub on_partial_failure: PartialFailure,
+    pub dead_letter_table: Option<String>,
+    pub schema_management: SchemaManagement,
+    pub columns: Option<Arc<ColumnMapping>>,
+    pub procedure: Option<ProcedureConfig>,
+    pub time_partition: Option<TimePartitionConfig>,
+    pub metrics: Option<MetricsConfig>,
+    pub endpoint: String,
+    pub password_file: Option<PasswordFile>,
+}
+
+#[derive(Clone)]
+pub struct MySqlService {
+    connection_pool: Pool<MySql>,
+    settings: Arc<MySqlSettings>,
+    /// The columns of the tables that have been created or altered to fit a batch.
+    known_tables: Arc<Mutex<HashMap<String, HashSet<String>>>>,
+    /// The partitions whose following partitions have been created.
+    created_ahead: Arc<Mutex<HashSet<String>>>,
+}
+
+impl MySqlService {
+    pub fn new(connection_pool: Pool<MySql>, settings: MySqlSettings) -> Self {
+        Self {
+            connection_pool,
+            settings: Arc::new(settings),
+            known_tables: Arc::default(),
+            created_ahead: Arc::default(),
+        }
+    }
+
+    /// Checks that the sink can connect, and that the procedure exists or it can insert into
+    /// the table if it is not templated, creating the table from the configured columns first
+    /// if needed.
+    pub async fn healthcheck(self, table: Template) -> crate::Result<()> {
+        sqlx::query("SELECT 1")
+            .execute(&self.connection_pool)
+            .await?;
+
+        if let Some(procedure) = &self.settings.procedure {
+            let (schema, name) = split_qualified(&procedure.name);
+            let exists = sqlx::query(
+                "SELECT 1 FROM information_schema.ROUTINES \
+                 WHERE ROUTINE_SCHEMA = COALESCE(?, DATABASE()) AND ROUTINE_NAME = ? \
+                 AND ROUTINE_TYPE = 'PROCEDURE'",
+            )
+            .bind(schema)
+            .bind(name)
+            .fetch_optional(&self.connection_pool)
+            .await?
+            .is_some();
+            if !exists {
+                return Err(format!("Procedure {:?} does not exist.", procedure.name).into());
+            }
+            return Ok(());
+        }
+
+        if table.is_dynamic() || self.settings.time_partition.is_some() {
+            return Ok(());
+        }
+        let table = table.get_ref();
+        let columns = self.settings.columns.as_deref();
+
+        if let Some(columns) = columns {
+            self.prepare_table(table, columns.definitions(Dialect::MySql))
+                .await?;
+        }
+
+        let existing = self.table_columns(table).await?;
+        check_table(
+            table,
+            self.settings.schema_management,
+            existing.as_ref(),
+            columns,
+        )?;
+        if existing.is_some() {
+            sqlx::query(&insert_probe(Dialect::MySql, table, columns))
+                .execute(&self.connection_pool)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Reads the password file again when `error` is the server rejecting the credentials,
+    /// returning whether the password changed since the request started with `generation`.
+    ///
+    /// Every request that was denied with an older password is retried, not only the first one
+    /// to find the new password in the file.
+    async fn refresh_credentials(&self, error: &sqlx::Error, generation: Option<u64>) -> bool {
+        let (Some(password_file), Some(generation)) = (&self.settings.password_file, generation)
+        else {
+            return false;
+        };
+        if !is_access_denied(error) {
+            return false;
+        }
+
+        let current = password_file.reload(|password| {
+            info!(message = "MySQL password changed, retrying with the new password.");
+            let options = (*self.connection_pool.connect_options())
+                .clone()
+                .password(password);
+            self.connection_pool.set_connect_options(options);
+        });
+        current.await.is_some_and(|current| current != generation)
+    }
+
+    /// Creates or alters `table` as configured, so that `columns` can be inserted into it.
+    async fn prepare_table(
+        &self,
+        table: &str,
+        columns: Vec<ColumnDefinition>,
+    ) -> Result<(), MySqlServiceError> {
+        let management = self.settings.schema_management;
+        if management == SchemaManagement::None {
+            return Ok(());
+        }
+
+        let mut known_tables = self.known_tables.lock().await;
+        if let Some(known) = known_tables.get(table)
+            && (management == SchemaManagement::Create
+                || columns.iter().all(|column| known.contains(&column.name)))
+        {
+            return Ok(());
+        }
+
+        // Creating the table is idempotent, so only altering it requires knowing its columns.
+        let existing = match management {
+            SchemaManagement::CreateAndAlter => self.table_columns(table).await?,
+            _ => None,
+        };
+        for statement in migration(
+            Dialect::MySql,
+            management,
+            table,
+            existing.as_ref(),
+            &columns,
+        ) {
+            sqlx::query(&statement)
+                .execute(&self.connection_pool)
+                .await
+                .context(MySqlSnafu)?;
+        }
+
+        let mut known = existing.unwrap_or_default();
+        known.extend(columns.into_iter().map(|column| column.name));
+        known_tables.insert(table.to_owned(), known);
+        Ok(())
+    }
+
+    /// Creates the partitions following `table` like it, the first time it is written to.
+    async fn create_ahead(&self, table: &str) -> Result<(), MySqlServiceError> {
+        let Some(time_partition) = &self.settings.time_partition else {
+            return Ok(());
+        };
+
+        let mut created_ahead = self.created_ahead.lock().await;
+        if created_ahead.contains(table) {
+            return Ok(());
+        }
+        for next in time_partition.following(table) {
+            sqlx::query(&format!(
+                "CREATE TABLE IF NOT EXISTS {} LIKE {}",
+                Dialect::MySql.quote_qualified(&next),
+                Dialect::MySql.quote_qualified(table),
+            ))
+            .execute(&self.connection_pool)
+            .await
+            .context(MySqlSnafu)?;
+        }
+        created_ahead.insert(table.to_owned());
+        Ok(())
+    }
+
+    /// The columns of `table`, or `None` if it does not exist.
+    async fn table_columns(
+        &self,
+        table: &str,
+    ) -> Result<Option<HashSet<String>>, MySqlServiceError> {
+        let (schema, name) = split_qualified(table);
+        let columns: Vec<String> = sqlx::query_scalar(
+            "SELECT COLUMN_NAME FROM information_schema.COLUMNS \
+             WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) AND TABLE_NAME = ?",
+        )
+        .bind(schema)
+        .bind(name)
+        .fetch_all(&self.connection_pool)
+        .await
+        .context(MySqlSnafu)?;
+
+        Ok((!columns.is_empty()).then(|| columns.into_iter().collect()))
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum MySqlServiceError {
+    #[snafu(display("Database error: {source}"))]
+    MySql { source: sqlx::Error },
+
+    #[snafu(display("Database rejected the credentials, which have since changed: {source}"))]
+    CredentialsChanged { source: sqlx::Error },
+}
+
+impl Service<DatabaseRequest> for MySqlService {
+    type Response = DatabaseResponse;
+    type Error = MySqlServiceError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: DatabaseRequest) -> Self::Future {
+        let credentials = self.clone();
+        // The generation of the password that the request starts with.
+        let generation = self
+            .settings
+            .password_file
+            .as_ref()
+            .map(PasswordFile::generation);
+        let service = self.clone();
+        let future = async move {
+            let settings = &service.settings;
+            let (rows, metric_rows) =
+                request.rows(settings.metrics.as_ref(), settings.columns.as_deref());
+            if settings.procedure.is_none() {
+                let columns = rows.column_definitions(Dialect::MySql, settings.columns.as_deref());
+                service.prepare_table(&request.table, columns).await?;
+                service.create_ahead(&request.table).await?;
+            }
+            if let Some(dead_letter_table) = &settings.dead_letter_table {
+                service
+                    .prepare_table(dead_letter_table, dead_letter_columns(Dialect::MySql))
+                    .await?;
+            }
+
+            let writer = RowWriter {
+                dialect: Dialect::MySql,
+                table: &request.table,
+                on_partial_failure: settings.on_partial_failure,
+                dead_letter_table: settings.dead_letter_table.as_deref(),
+                statements: |rows: Rows| match &settings.procedure {
+                    Some(procedure) => procedure.calls(Dialect::MySql, rows),
+                    None => InsertStatement::chunked(
+                        Dialect::MySql,
+                        &request.table,
+                        rows,
+                        &settings.on_conflict,
+                    ),
+                },
+            };
+            let rejected = writer
+                .write(&service.connection_pool, rows)
+                .await
+                .context(MySqlSnafu)?;
+            request.reject_rows(metric_rows.as_ref(), rejected);
+
+            emit!(EndpointBytesSent {
+                byte_size: request.metadata.request_encoded_size(),
+                protocol: MYSQL_PROTOCOL,
+                endpoint: &settings.endpoint,
+            });
+
+            Ok(DatabaseResponse::new(request.metadata))
+        };
+
+        Box::pin(async move {
+            match future.await {
+                Err(MySqlServiceError::MySql { source }) => {
+                    if credentials.refresh_credentials(&source, generation).await {
+                        Err(MySqlServiceError::CredentialsChanged { source })
+                    } else {
+                        Err(MySqlServiceError::MySql { source })
+                    }
+                }
+                result => result,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn reloads_changed_password() {
+        let path = crate::test_util::temp_dir().join("password");
+        std::fs::write(&path, "hunter2\n").unwrap();
+        let password_file = PasswordFile::new(path.clone()).await.unwrap();
+        assert_eq!(password_file.password(), "hunter2");
+        assert_eq!(password_file.reload(|_| panic!("unchanged")).await, Some(0));
+
+        std::fs::write(&path, "correct horse\n").unwrap();
+        let mut updated = None;
+        let generation = password_file
+            .reload(|password| updated = Some(password.to_owned()))
+            .await;
+        assert_eq!(generation, Some(1));
+        assert_eq!(updated.as_deref(), Some("correct horse"));
+        assert_eq!(password_file.password(), "correct horse");
+
+        // Requests that started before the change still see a newer generation.
+        assert_eq!(password_file.reload(|_| panic!("unchanged")).await, Some(1));
+    }
+}
@@ -0,0 +1,20 @@
+use vector_lib::configurable::configurable_component;
+
+/// How a batch is handled when some of its rows fail to insert.
+///
+/// Every batch is written in a single transaction, so either all of the rows that are kept
+/// are committed, or none are.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PartialFailure {
+    /// The transaction is rolled back and the whole batch fails, to be retried if the error is
+    /// transient and rejected otherwise.
+    #[default]
+    RetryAll,
+
+    /// When the error is not transient, the batch is split in halves that are inserted
+    /// separately, within the same transaction, until the rows that fail on their own are
+    /// found. Only those rows are rejected, and all others are committed.
+    SplitBatch,
+}
@@ -0,0 +1,174 @@
+use std::collections::HashSet;
+
+use super::TimePartitionConfig;
+use crate::{internal_events::DatabaseTableRejectedError, sinks::prelude::*};
+
+/// Partitions events by the table they are inserted into.
+///
+/// Events whose table cannot be rendered, or renders to a table the sink may not write to, are
+/// dropped. With time partitioning, the suffix of the partition is appended to the table after
+/// it has been checked.
+pub struct TablePartitioner {
+    table: Template,
+    allowed_tables: Option<HashSet<String>>,
+    segments: usize,
+    suffix: Option<Template>,
+}
+
+impl TablePartitioner {
+    pub fn new(
+        table: Template,
+        allowed_tables: Option<Vec<String>>,
+        time_partition: Option<&TimePartitionConfig>,
+    ) -> Self {
+        let segments = qualified_segments(table.get_ref());
+        Self {
+            table,
+            allowed_tables: allowed_tables.map(|tables| tables.into_iter().collect()),
+            segments,
+            suffix: time_partition.map(TimePartitionConfig::suffix),
+        }
+    }
+}
+
+/// The number of `.`-separated parts of a table name template, ignoring any `.` that is part of
+/// a `{{ field.path }}` expression.
+fn qualified_segments(template: &str) -> usize {
+    let mut segments = 1;
+    let mut rest = template;
+    while let Some(index) = rest.find(['.', '{']) {
+        if rest[index..].starts_with("{{") {
+            match rest[index..].find("}}") {
+                Some(end) => rest = &rest[index + end + 2..],
+                None => break,
+            }
+        } else {
+            if rest.as_bytes()[index] == b'.' {
+                segments += 1;
+            }
+            rest = &rest[index + 1..];
+        }
+    }
+    segments
+}
+
+impl Partitioner for TablePartitioner {
+    type Item = Event;
+    type Key = Option<String>;
+
+    fn partition(&self, item: &Self::Item) -> Self::Key {
+        let table = self
+            .table
+            .render_string(item)
+            .map_err(|error| {
+                emit!(TemplateRenderingError {
+                    error,
+                    field: Some("table"),
+                    drop_event: true,
+                });
+            })
+            .ok()?;
+
+        // A `.` coming from an event field would otherwise quietly change the database or schema
+        // that the table is qualified with.
+        if table.split('.').count() != self.segments {
+            emit!(DatabaseTableRejectedError {
+                table: &table,
+                reason: "Rendered table has a different qualification than the template.",
+            });
+            return None;
+        }
+
+        if let Some(allowed_tables) = &self.allowed_tables
+            && !allowed_tables.contains(&table)
+        {
+            emit!(DatabaseTableRejectedError {
+                table: &table,
+                reason: "Rendered table is not in the allowed tables.",
+            });
+            return None;
+        }
+
+        match &self.suffix {
+            Some(suffix) => {
+                let suffix = suffix
+                    .render_string(item)
+                    .map_err(|error| {
+                        emit!(TemplateRenderingError {
+                            error,
+                            field: Some("time_partition"),
+                            drop_event: true,
+                        });
+                    })
+                    .ok()?;
+                Some(format!("{table}_{suffix}"))
+            }
+            None => Some(table),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use vector_lib::event::LogEvent;
+
+    use super::*;
+
+    fn partition(partitioner: &TablePartitioner, service: &str) -> Option<String> {
+        let mut event = LogEvent::from("message");
+        event.insert("service", service);
+        partitioner.partition(&event.into())
+    }
+
+    #[test]
+    fn counts_segments_outside_of_templates() {
+        assert_eq!(qualified_segments("logs"), 1);
+        assert_eq!(qualified_segments("db.logs_{{ service }}"), 2);
+        assert_eq!(qualified_segments("logs_{{ .kubernetes.namespace }}"), 1);
+    }
+
+    #[test]
+    fn renders_table() {
+        let partitioner = TablePartitioner::new(
+            Template::try_from("logs_{{ service }}").unwrap(),
+            None,
+            None,
+        );
+        assert_eq!(partition(&partitioner, "api").as_deref(), Some("logs_api"));
+        assert_eq!(partition(&partitioner, "other.api"), None);
+    }
+
+    #[test]
+    fn rejects_tables_not_allowed() {
+        let partitioner = TablePartitioner::new(
+            Template::try_from("logs_{{ service }}").unwrap(),
+            Some(vec!["logs_api".into()]),
+            None,
+        );
+        assert_eq!(partition(&partitioner, "api").as_deref(), Some("logs_api"));
+        assert_eq!(partition(&partitioner, "web"), None);
+    }
+
+    #[test]
+    fn appends_partition_suffix() {
+        let time_partition =
+            toml::from_str::<TimePartitionConfig>(r#"interval = "daily""#).unwrap();
+        let partitioner = TablePartitioner::new(
+            Template::try_from("logs_{{ service }}").unwrap(),
+            Some(vec!["logs_api".into()]),
+            Some(&time_partition),
+        );
+
+        let mut event = LogEvent::from("message");
+        event.insert("service", "api");
+        event.insert(
+            "timestamp",
+            chrono::Utc.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap(),
+        );
+        assert_eq!(
+            partitioner.partition(&event.into()).as_deref(),
+            Some("logs_api_20250102")
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use serde_json::{Map, Value as JsonValue};
+use vector_lib::configurable::configurable_component;
+
+use super::{Dialect, InsertStatement, Rows, SqlValue};
+
+/// A stored procedure that rows are written through, instead of being inserted directly.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ProcedureConfig {
+    /// The name of the procedure, optionally qualified with a database.
+    #[configurable(metadata(docs::examples = "insert_log"))]
+    pub name: String,
+
+    /// How rows are passed to the procedure.
+    #[configurable(derived)]
+    #[serde(default)]
+    pub mode: ProcedureMode,
+}
+
+/// How rows are passed to a stored procedure.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcedureMode {
+    /// The procedure is called once per row, with the configured `columns` as its arguments, in
+    /// order.
+    #[default]
+    Row,
+
+    /// The procedure is called once per batch, with a single argument holding the rows as a
+    /// JSON array of objects keyed by column name.
+    Batch,
+}
+
+impl ProcedureConfig {
+    /// Renders the `CALL`s that write `rows`.
+    pub fn calls(&self, dialect: Dialect, rows: Rows) -> Vec<InsertStatement> {
+        let name = dialect.quote_qualified(&self.name);
+        let (columns, values) = rows.into_parts();
+        match self.mode {
+            ProcedureMode::Row => {
+                let placeholders = vec!["?"; columns.len()].join(", ");
+                values
+                    .into_iter()
+                    .map(|row| InsertStatement {
+                        sql: format!("CALL {name}({placeholders})"),
+                        params: row
+                            .into_iter()
+                            .map(|value| value.unwrap_or(SqlValue::Null))
+                            .collect(),
+                    })
+                    .collect()
+            }
+            ProcedureMode::Batch => {
+                let rows = values
+                    .into_iter()
+                    .map(|row| {
+                        let object = columns
+                            .iter()
+                            .zip(row)
+                            .filter_map(|(column, value)| Some((column.clone(), value?.into())))
+                            .collect::<Map<_, _>>();
+                        JsonValue::Object(object)
+                    })
+                    .collect::<Vec<_>>();
+                vec![InsertStatement {
+                    sql: format!("CALL {name}(?)"),
+                    params: vec![SqlValue::Json(JsonValue::Array(rows).to_string())],
+                }]
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexMap;
+    use vector_lib::{TimeZone, event::LogEvent};
+    use vrl::btreemap;
+
+    use super::*;
+    use crate::sinks::database::{ColumnConfig, ColumnMapping};
+
+    fn rows() -> Rows {
+        let columns =
+            toml::from_str::<IndexMap<String, ColumnConfig>>("message = {}\nid = {}").unwrap();
+        let mapping = ColumnMapping::new(&columns, None, TimeZone::default())
+            .unwrap()
+            .unwrap();
+        let first = LogEvent::from(btreemap! { "message" => "one", "id" => 1 });
+        let second = LogEvent::from(btreemap! { "message" => "two" });
+        Rows::from_mapping([&first, &second], &mapping)
+    }
+
+    fn procedure(mode: ProcedureMode) -> ProcedureConfig {
+        ProcedureConfig {
+            name: "db.insert_log".into(),
+            mode,
+        }
+    }
+
+    #[test]
+    fn calls_procedure_per_row() {
+        let calls = procedure(ProcedureMode::Row).calls(Dialect::MySql, rows());
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].sql, "CALL `db`.`insert_log`(?, ?)");
+        assert_eq!(
+            calls[1].params,
+            [SqlValue::Text("two".into()), SqlValue::Null]
+        );
+    }
+
+    #[test]
+    fn calls_procedure_per_batch() {
+        let calls = procedure(ProcedureMode::Batch).calls(Dialect::MySql, rows());
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].sql, "CALL `db`.`insert_log`(?)");
+        assert_eq!(
+            calls[0].params,
+            [SqlValue::Json(
+                r#"[{"message":"one","id":1},{"message":"two"}]"#.into()
+            )]
+        );
+    }
+}
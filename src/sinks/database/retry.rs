@@ -0,0 +1,109 @@
+use sqlx::error::DatabaseError;
+
+use super::Dialect;
+
+/// Whether `error` is transient, so that retrying the same request can succeed.
+///
+/// Errors caused by the rows themselves, such as constraint violations, type mismatches or
+/// unknown columns, fail the same way on every attempt and are not retried, so that the events
+/// are rejected instead of blocking the sink.
+pub fn is_retriable(dialect: Dialect, error: &sqlx::Error) -> bool {
+    match error {
+        sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut => true,
+        sqlx::Error::Database(error) => dialect.is_transient(error.as_ref()),
+        _ => false,
+    }
+}
+
+impl Dialect {
+    fn is_transient(self, error: &dyn DatabaseError) -> bool {
+        match self {
+            #[cfg(feature = "sinks-mysql")]
+            Self::MySql => error
+                .try_downcast_ref::<sqlx::mysql::MySqlDatabaseError>()
+                .is_some_and(|error| {
+                    matches!(
+                        error.number(),
+                        // Too many connections, server shutdown in progress, lock wait timeout,
+                        // deadlock, and the server going away or the connection being lost.
+                        1040 | 1053 | 1205 | 1213 | 2006 | 2013
+                    )
+                }),
+            // Serialization failures, deadlocks, and the SQLSTATE classes of connection errors,
+            // a lack of resources, and an intervening shutdown.
+            #[cfg(feature = "sinks-postgres")]
+            Self::Postgres => error.code().is_some_and(|code| {
+                matches!(&*code, "40001" | "40P01")
+                    || ["08", "53", "57P"]
+                        .iter()
+                        .any(|class| code.starts_with(class))
+            }),
+            // Extended result codes keep the primary code in their lowest byte.
+            #[cfg(feature = "sinks-sqlite")]
+            Self::Sqlite => error
+                .code()
+                .and_then(|code| code.parse::<u32>().ok())
+                .is_some_and(|code| matches!(code & 0xff, SQLITE_BUSY | SQLITE_LOCKED)),
+        }
+    }
+}
+
+#[cfg(feature = "sinks-sqlite")]
+const SQLITE_BUSY: u32 = 5;
+#[cfg(feature = "sinks-sqlite")]
+const SQLITE_LOCKED: u32 = 6;
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::*;
+
+    #[cfg(feature = "sinks-sqlite")]
+    #[tokio::test]
+    async fn classifies_sqlite_errors() {
+        use sqlx::{Connection, Executor, SqliteConnection, sqlite::SqliteConnectOptions};
+
+        let path = crate::test_util::temp_dir().join("events.db");
+        let options = SqliteConnectOptions::new()
+            .filename(&path)
+            .create_if_missing(true)
+            .busy_timeout(std::time::Duration::ZERO);
+        let mut holder = SqliteConnection::connect_with(&options).await.unwrap();
+        let mut writer = SqliteConnection::connect_with(&options).await.unwrap();
+        holder
+            .execute("CREATE TABLE logs (id INTEGER PRIMARY KEY)")
+            .await
+            .unwrap();
+
+        let duplicate = writer
+            .execute("INSERT INTO logs VALUES (1), (1)")
+            .await
+            .unwrap_err();
+        assert!(!is_retriable(Dialect::Sqlite, &duplicate));
+
+        holder.execute("BEGIN EXCLUSIVE").await.unwrap();
+        let busy = writer
+            .execute("INSERT INTO logs VALUES (2)")
+            .await
+            .unwrap_err();
+        assert!(is_retriable(Dialect::Sqlite, &busy));
+    }
+
+    #[test]
+    fn connection_errors_are_retriable() {
+        for dialect in [
+            #[cfg(feature = "sinks-mysql")]
+            Dialect::MySql,
+            #[cfg(feature = "sinks-postgres")]
+            Dialect::Postgres,
+            #[cfg(feature = "sinks-sqlite")]
+            Dialect::Sqlite,
+        ] {
+            let reset = sqlx::Error::Io(io::ErrorKind::ConnectionReset.into());
+            assert!(is_retriable(dialect, &reset));
+            assert!(is_retriable(dialect, &sqlx::Error::PoolTimedOut));
+            assert!(!is_retriable(dialect, &sqlx::Error::RowNotFound));
+        }
+    }
+}
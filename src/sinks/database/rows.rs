@@ -0,0 +1,432 @@
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use vector_lib::event::{LogEvent, Value};
+use vrl::compiler::runtime::Runtime;
+
+use super::{ColumnMapping, OnConflict};
+
+/// A value bound to a statement placeholder.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SqlValue {
+    Null,
+    Boolean(bool),
+    Integer(i64),
+    Float(f64),
+    Text(String),
+    Timestamp(DateTime<Utc>),
+    /// Objects and arrays, serialized as JSON text.
+    Json(String),
+}
+
+impl From<&Value> for SqlValue {
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::Bytes(bytes) => Self::Text(String::from_utf8_lossy(bytes).into_owned()),
+            Value::Regex(regex) => Self::Text(regex.as_str().to_owned()),
+            Value::Integer(integer) => Self::Integer(*integer),
+            Value::Float(float) => Self::Float(float.into_inner()),
+            Value::Boolean(boolean) => Self::Boolean(*boolean),
+            Value::Timestamp(timestamp) => Self::Timestamp(*timestamp),
+            Value::Object(_) | Value::Array(_) => Self::Json(
+                serde_json::to_string(value).expect("event values are always serializable"),
+            ),
+            Value::Null => Self::Null,
+        }
+    }
+}
+
+impl From<SqlValue> for serde_json::Value {
+    fn from(value: SqlValue) -> Self {
+        match value {
+            SqlValue::Null => Self::Null,
+            SqlValue::Boolean(value) => value.into(),
+            SqlValue::Integer(value) => value.into(),
+            SqlValue::Float(value) => value.into(),
+            SqlValue::Text(value) => value.into(),
+            SqlValue::Timestamp(value) => value.to_rfc3339_opts(SecondsFormat::AutoSi, true).into(),
+            SqlValue::Json(value) => serde_json::from_str(&value).unwrap_or(Self::String(value)),
+        }
+    }
+}
+
+/// The SQL flavour a statement is rendered for.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Dialect {
+    #[cfg(feature = "sinks-mysql")]
+    MySql,
+    /// Only used for the conflict handling, checks, and retries of the `postgres` sink, which
+    /// renders its own `INSERT` statements.
+    #[cfg(feature = "sinks-postgres")]
+    Postgres,
+    #[cfg(feature = "sinks-sqlite")]
+    Sqlite,
+}
+
+impl Dialect {
+    /// Quotes a single identifier, escaping any embedded quote characters.
+    pub fn quote_identifier(self, identifier: &str) -> String {
+        match self {
+            #[cfg(feature = "sinks-mysql")]
+            Self::MySql => format!("`{}`", identifier.replace('`', "``")),
+            #[cfg(feature = "sinks-postgres")]
+            Self::Postgres => format!("\"{}\"", identifier.replace('"', "\"\"")),
+            #[cfg(feature = "sinks-sqlite")]
+            Self::Sqlite => format!("\"{}\"", identifier.replace('"', "\"\"")),
+        }
+    }
+
+    /// Quotes a possibly schema-qualified name such as `database.table`.
+    pub fn quote_qualified(self, name: &str) -> String {
+        name.split('.')
+            .map(|part| self.quote_identifier(part))
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    /// The maximum number of placeholders a single statement can bind.
+    const fn max_params(self) -> usize {
+        match self {
+            #[cfg(feature = "sinks-mysql")]
+            Self::MySql => 65_535,
+            #[cfg(feature = "sinks-postgres")]
+            Self::Postgres => 65_535,
+            // `SQLITE_MAX_VARIABLE_NUMBER` as compiled into SQLite since 3.32.0.
+            #[cfg(feature = "sinks-sqlite")]
+            Self::Sqlite => 32_766,
+        }
+    }
+
+    /// The expression used for a column that a row does not set, or `None` if it is bound as
+    /// `NULL` like any other value.
+    ///
+    /// SQLite does not accept `DEFAULT` in a `VALUES` list, so missing fields are written as
+    /// `NULL` there. Binding them keeps the statement the same whichever fields are missing.
+    const fn missing_value(self) -> Option<&'static str> {
+        match self {
+            #[cfg(feature = "sinks-mysql")]
+            Self::MySql => Some("DEFAULT"),
+            #[cfg(feature = "sinks-postgres")]
+            Self::Postgres => Some("DEFAULT"),
+            #[cfg(feature = "sinks-sqlite")]
+            Self::Sqlite => None,
+        }
+    }
+}
+
+/// The rows of a batch, sharing one column list.
+///
+/// The columns are the union of the top-level fields of all events in the batch, so that a
+/// single multi-row `INSERT` can be issued. A field missing from an event is left to the
+/// column default where the dialect supports it.
+#[derive(Clone, Debug, Default)]
+pub struct Rows {
+    columns: Vec<String>,
+    values: Vec<Vec<Option<SqlValue>>>,
+}
+
+impl Rows {
+    pub fn from_logs<'a>(logs: impl IntoIterator<Item = &'a LogEvent>) -> Self {
+        let maps = logs.into_iter().map(LogEvent::as_map).collect::<Vec<_>>();
+
+        let columns = maps
+            .iter()
+            .flatten()
+            .flat_map(|map| map.keys())
+            .map(|key| key.as_str())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(ToOwned::to_owned)
+            .collect::<Vec<_>>();
+
+        let values = maps
+            .into_iter()
+            .map(|map| {
+                columns
+                    .iter()
+                    .map(|column| {
+                        map.and_then(|map| map.get(column.as_str()))
+                            .map(SqlValue::from)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self { columns, values }
+    }
+
+    /// Builds the rows of the columns in `mapping`, collecting any other field into its
+    /// overflow column, or else ignoring them.
+    pub fn from_mapping<'a>(
+        logs: impl IntoIterator<Item = &'a LogEvent>,
+        mapping: &ColumnMapping,
+    ) -> Self {
+        let columns = mapping.names().map(ToOwned::to_owned).collect();
+        let mut runtime = Runtime::default();
+        let values = logs
+            .into_iter()
+            .map(|log| {
+                let mut row = mapping
+                    .values(log, &mut runtime)
+                    .map(|value| value.as_deref().map(SqlValue::from))
+                    .collect::<Vec<_>>();
+                if mapping.overflow_column().is_some() {
+                    row.push(mapping.overflow(log).as_ref().map(SqlValue::from));
+                }
+                row
+            })
+            .collect();
+
+        Self { columns, values }
+    }
+
+    #[cfg(feature = "sinks-mysql")]
+    pub fn into_parts(self) -> (Vec<String>, Vec<Vec<Option<SqlValue>>>) {
+        (self.columns, self.values)
+    }
+
+    pub const fn row_count(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Splits the rows in two halves, the first being the smaller one for an odd number of rows.
+    pub fn halve(mut self) -> (Self, Self) {
+        let second = self.values.split_off(self.values.len() / 2);
+        let second = Self {
+            columns: self.columns.clone(),
+            values: second,
+        };
+        (self, second)
+    }
+
+    /// Each column together with its first non-null value in the batch, if any.
+    pub fn first_values(&self) -> impl Iterator<Item = (&str, Option<&SqlValue>)> {
+        self.columns.iter().enumerate().map(|(index, column)| {
+            let value = self
+                .values
+                .iter()
+                .filter_map(|row| row[index].as_ref())
+                .find(|value| **value != SqlValue::Null);
+            (column.as_str(), value)
+        })
+    }
+
+    /// Splits the rows into consecutive chunks that bind at most `max_params` values each.
+    ///
+    /// Every chunk holds a power of two rows, so that batches of any size share statements, which
+    /// the driver prepares once per connection and reuses from its statement cache. MySQL writes
+    /// a literal `DEFAULT` for each field that a row does not set, so there its statements are
+    /// only reused between batches that leave the same fields unset, such as batches whose
+    /// events all set every column. Both MySQL and SQLite re-prepare a cached statement themselves
+    /// when its table is altered, so the cache needs no invalidation when the sink changes the
+    /// schema. A single row that binds more values than `max_params` is still put in a chunk of
+    /// its own, and left for the database to reject.
+    fn split(self, max_params: usize) -> Vec<Self> {
+        let max_rows = (max_params / self.columns.len().max(1)).max(1);
+        let max_rows: usize = 1 << max_rows.ilog2();
+
+        let mut chunks = Vec::new();
+        let mut values = self.values.into_iter();
+        let mut remaining = values.len();
+        while remaining > 0 {
+            let rows = max_rows.min(1 << remaining.ilog2());
+            chunks.push(Self {
+                columns: self.columns.clone(),
+                values: values.by_ref().take(rows).collect(),
+            });
+            remaining -= rows;
+        }
+        chunks
+    }
+}
+
+/// A rendered statement together with the values for its placeholders, in order.
+#[derive(Debug)]
+pub struct InsertStatement {
+    pub sql: String,
+    pub params: Vec<SqlValue>,
+}
+
+impl InsertStatement {
+    /// Renders the multi-row `INSERT`s of `rows` into `table`, in chunks that fit the
+    /// placeholder limit of the dialect.
+    pub fn chunked(
+        dialect: Dialect,
+        table: &str,
+        rows: Rows,
+        on_conflict: &OnConflict,
+    ) -> Vec<Self> {
+        rows.split(dialect.max_params())
+            .into_iter()
+            .map(|rows| Self::new(dialect, table, rows, on_conflict))
+            .collect()
+    }
+
+    /// Renders a single multi-row `INSERT` of `rows` into `table`.
+    pub fn new(dialect: Dialect, table: &str, rows: Rows, on_conflict: &OnConflict) -> Self {
+        let columns = rows
+            .columns
+            .iter()
+            .map(|column| dialect.quote_identifier(column))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut params = Vec::new();
+        let tuples = rows
+            .values
+            .into_iter()
+            .map(|row| {
+                let placeholders = row
+                    .into_iter()
+                    .map(|value| match (value, dialect.missing_value()) {
+                        (None, Some(missing)) => missing,
+                        (value, _) => {
+                            params.push(value.unwrap_or(SqlValue::Null));
+                            "?"
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("({placeholders})")
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut sql = format!(
+            "INSERT INTO {} ({columns}) VALUES {tuples}",
+            dialect.quote_qualified(table)
+        );
+        if let Some(clause) = on_conflict.clause(dialect, &rows.columns) {
+            sql.push_str(&clause);
+        }
+
+        Self { sql, params }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vrl::btreemap;
+
+    use super::*;
+
+    #[test]
+    fn converts_values() {
+        assert_eq!(
+            SqlValue::from(&Value::from("foo")),
+            SqlValue::Text("foo".into())
+        );
+        assert_eq!(SqlValue::from(&Value::Integer(3)), SqlValue::Integer(3));
+        assert_eq!(SqlValue::from(&Value::Null), SqlValue::Null);
+        assert_eq!(
+            SqlValue::from(&Value::from(btreemap! { "a" => 1 })),
+            SqlValue::Json(r#"{"a":1}"#.into())
+        );
+    }
+
+    #[test]
+    fn converts_values_to_json() {
+        let timestamp = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        assert_eq!(
+            serde_json::Value::from(SqlValue::Timestamp(timestamp)),
+            serde_json::json!("2023-11-14T22:13:20Z")
+        );
+        assert_eq!(
+            serde_json::Value::from(SqlValue::Json(r#"{"a":1}"#.into())),
+            serde_json::json!({ "a": 1 })
+        );
+        assert_eq!(
+            serde_json::Value::from(SqlValue::Json("{".into())),
+            serde_json::json!("{")
+        );
+    }
+
+    #[cfg(feature = "sinks-mysql")]
+    #[test]
+    fn quotes_identifiers() {
+        assert_eq!(Dialect::MySql.quote_identifier("a`b"), "`a``b`");
+        assert_eq!(Dialect::MySql.quote_qualified("db.logs"), "`db`.`logs`");
+    }
+
+    #[cfg(feature = "sinks-sqlite")]
+    #[test]
+    fn quotes_sqlite_identifiers() {
+        assert_eq!(Dialect::Sqlite.quote_identifier("a\"b"), "\"a\"\"b\"");
+        assert_eq!(
+            Dialect::Sqlite.quote_qualified("main.logs"),
+            "\"main\".\"logs\""
+        );
+    }
+
+    #[cfg(feature = "sinks-mysql")]
+    #[test]
+    fn renders_multi_row_insert() {
+        let first = LogEvent::from(btreemap! { "message" => "one", "id" => 1 });
+        let second = LogEvent::from(btreemap! { "message" => "two", "host" => "a" });
+
+        let rows = Rows::from_logs([&first, &second]);
+        assert_eq!(rows.columns, ["host", "id", "message"]);
+
+        let statement = InsertStatement::new(Dialect::MySql, "logs", rows, &OnConflict::default());
+        assert_eq!(
+            statement.sql,
+            "INSERT INTO `logs` (`host`, `id`, `message`) VALUES (DEFAULT, ?, ?), (?, DEFAULT, ?)"
+        );
+        assert_eq!(
+            statement.params,
+            vec![
+                SqlValue::Integer(1),
+                SqlValue::Text("one".into()),
+                SqlValue::Text("a".into()),
+                SqlValue::Text("two".into()),
+            ]
+        );
+    }
+
+    #[cfg(feature = "sinks-sqlite")]
+    #[test]
+    fn renders_sqlite_missing_values_as_null() {
+        let first = LogEvent::from(btreemap! { "message" => "one" });
+        let second = LogEvent::from(btreemap! { "id" => 2 });
+
+        let statement = InsertStatement::new(
+            Dialect::Sqlite,
+            "logs",
+            Rows::from_logs([&first, &second]),
+            &OnConflict::default(),
+        );
+        assert_eq!(
+            statement.sql,
+            r#"INSERT INTO "logs" ("id", "message") VALUES (?, ?), (?, ?)"#
+        );
+        assert_eq!(
+            statement.params,
+            vec![
+                SqlValue::Null,
+                SqlValue::Text("one".into()),
+                SqlValue::Integer(2),
+                SqlValue::Null,
+            ]
+        );
+    }
+
+    #[test]
+    fn splits_rows_by_placeholders() {
+        let log = LogEvent::from(btreemap! { "a" => 1, "b" => 2 });
+        let chunk_sizes = |count, max_params| {
+            Rows::from_logs(std::iter::repeat_n(&log, count))
+                .split(max_params)
+                .iter()
+                .map(|chunk| chunk.values.len())
+                .collect::<Vec<_>>()
+        };
+
+        // Up to four rows of two values fit ten placeholders.
+        assert_eq!(chunk_sizes(11, 10), [4, 4, 2, 1]);
+        assert_eq!(chunk_sizes(7, 100), [4, 2, 1]);
+        assert_eq!(chunk_sizes(0, 10), Vec::<usize>::new());
+
+        // A row that exceeds the limit by itself still gets its own chunk.
+        assert_eq!(chunk_sizes(1, 1), [1]);
+    }
+}
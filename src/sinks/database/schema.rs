@@ -0,0 +1,227 @@
+use std::collections::HashSet;
+
+use vector_lib::configurable::configurable_component;
+
+use super::{ColumnMapping, Dialect, Rows, SqlValue};
+
+/// How the sink manages the tables it writes to.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaManagement {
+    /// Tables are expected to exist with all the columns that are written.
+    #[default]
+    None,
+
+    /// A table that does not exist is created, with a column for every field of the first batch
+    /// written to it.
+    Create,
+
+    /// Like `create`, and columns are added to an existing table whenever a batch has fields
+    /// that the table has no column for.
+    CreateAndAlter,
+}
+
+/// A column, typed after the values that are written to it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnDefinition {
+    pub name: String,
+    pub sql_type: String,
+    pub nullable: bool,
+}
+
+impl ColumnDefinition {
+    fn render(&self, dialect: Dialect) -> String {
+        let mut definition = dialect.quote_identifier(&self.name);
+        if !self.sql_type.is_empty() {
+            definition.push(' ');
+            definition.push_str(&self.sql_type);
+        }
+        if !self.nullable {
+            definition.push_str(" NOT NULL");
+        }
+        definition
+    }
+}
+
+impl Dialect {
+    /// The column type for values like `value`, or for a column that has only seen `NULL`.
+    pub const fn column_type(self, value: Option<&SqlValue>) -> &'static str {
+        match self {
+            #[cfg(feature = "sinks-mysql")]
+            Self::MySql => match value {
+                Some(SqlValue::Boolean(_)) => "BOOLEAN",
+                Some(SqlValue::Integer(_)) => "BIGINT",
+                Some(SqlValue::Float(_)) => "DOUBLE",
+                Some(SqlValue::Timestamp(_)) => "DATETIME(6)",
+                Some(SqlValue::Json(_)) => "JSON",
+                Some(SqlValue::Text(_) | SqlValue::Null) | None => "TEXT",
+            },
+            #[cfg(feature = "sinks-postgres")]
+            Self::Postgres => match value {
+                Some(SqlValue::Boolean(_)) => "BOOLEAN",
+                Some(SqlValue::Integer(_)) => "BIGINT",
+                Some(SqlValue::Float(_)) => "DOUBLE PRECISION",
+                Some(SqlValue::Timestamp(_)) => "TIMESTAMPTZ",
+                Some(SqlValue::Json(_)) => "JSONB",
+                Some(SqlValue::Text(_) | SqlValue::Null) | None => "TEXT",
+            },
+            // Columns without a declared type take values of any type as they are.
+            #[cfg(feature = "sinks-sqlite")]
+            Self::Sqlite => match value {
+                Some(SqlValue::Boolean(_) | SqlValue::Integer(_)) => "INTEGER",
+                Some(SqlValue::Float(_)) => "REAL",
+                Some(SqlValue::Text(_) | SqlValue::Timestamp(_) | SqlValue::Json(_)) => "TEXT",
+                Some(SqlValue::Null) | None => "",
+            },
+        }
+    }
+
+    /// The column type for a column holding a JSON document.
+    pub const fn json_type(self) -> &'static str {
+        match self {
+            #[cfg(feature = "sinks-mysql")]
+            Self::MySql => "JSON",
+            #[cfg(feature = "sinks-postgres")]
+            Self::Postgres => "JSONB",
+            #[cfg(feature = "sinks-sqlite")]
+            Self::Sqlite => "TEXT",
+        }
+    }
+}
+
+impl Rows {
+    /// The columns of the batch, typed as configured in `mapping` or else after their first
+    /// non-null value.
+    pub fn column_definitions(
+        &self,
+        dialect: Dialect,
+        mapping: Option<&ColumnMapping>,
+    ) -> Vec<ColumnDefinition> {
+        self.first_values()
+            .map(|(name, value)| ColumnDefinition {
+                name: name.to_owned(),
+                sql_type: mapping
+                    .and_then(|mapping| mapping.sql_type(name, dialect))
+                    .unwrap_or_else(|| dialect.column_type(value))
+                    .to_owned(),
+                nullable: mapping.is_none_or(|mapping| mapping.is_nullable(name)),
+            })
+            .collect()
+    }
+}
+
+/// The statements that create `table`, or add the columns it is missing, so that `columns` can
+/// be written to it.
+///
+/// `existing` holds the columns of the table, or is `None` if the table does not exist.
+pub fn migration(
+    dialect: Dialect,
+    management: SchemaManagement,
+    table: &str,
+    existing: Option<&HashSet<String>>,
+    columns: &[ColumnDefinition],
+) -> Vec<String> {
+    let table = dialect.quote_qualified(table);
+    match (management, existing) {
+        (SchemaManagement::None, _) => Vec::new(),
+        (_, None) => {
+            let columns = columns
+                .iter()
+                .map(|column| column.render(dialect))
+                .collect::<Vec<_>>()
+                .join(", ");
+            vec![format!("CREATE TABLE IF NOT EXISTS {table} ({columns})")]
+        }
+        (SchemaManagement::Create, Some(_)) => Vec::new(),
+        (SchemaManagement::CreateAndAlter, Some(existing)) => columns
+            .iter()
+            .filter(|column| !existing.contains(&column.name))
+            .map(|column| format!("ALTER TABLE {table} ADD COLUMN {}", column.render(dialect)))
+            .collect(),
+    }
+}
+
+/// Splits a possibly qualified table name into its schema and table.
+pub fn split_qualified(table: &str) -> (Option<&str>, &str) {
+    match table.rsplit_once('.') {
+        Some((schema, table)) => (Some(schema), table),
+        None => (None, table),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_lib::event::{LogEvent, Value};
+    use vrl::btreemap;
+
+    use super::*;
+
+    #[cfg(feature = "sinks-mysql")]
+    #[test]
+    fn renders_mysql_migrations() {
+        let first = LogEvent::from(btreemap! { "id" => 1, "tags" => Value::Null });
+        let second = LogEvent::from(btreemap! { "tags" => vec!["a"], "ok" => true });
+        let columns = Rows::from_logs([&first, &second]).column_definitions(Dialect::MySql, None);
+
+        assert_eq!(
+            migration(
+                Dialect::MySql,
+                SchemaManagement::Create,
+                "db.logs",
+                None,
+                &columns
+            ),
+            ["CREATE TABLE IF NOT EXISTS `db`.`logs` (`id` BIGINT, `ok` BOOLEAN, `tags` JSON)"]
+        );
+
+        let existing = HashSet::from(["id".to_owned()]);
+        assert!(
+            migration(
+                Dialect::MySql,
+                SchemaManagement::Create,
+                "logs",
+                Some(&existing),
+                &columns
+            )
+            .is_empty()
+        );
+        assert_eq!(
+            migration(
+                Dialect::MySql,
+                SchemaManagement::CreateAndAlter,
+                "logs",
+                Some(&existing),
+                &columns
+            ),
+            [
+                "ALTER TABLE `logs` ADD COLUMN `ok` BOOLEAN",
+                "ALTER TABLE `logs` ADD COLUMN `tags` JSON",
+            ]
+        );
+    }
+
+    #[cfg(feature = "sinks-sqlite")]
+    #[test]
+    fn leaves_null_sqlite_columns_untyped() {
+        let log = LogEvent::from(btreemap! { "id" => 1, "missing" => Value::Null });
+        let columns = Rows::from_logs([&log]).column_definitions(Dialect::Sqlite, None);
+
+        assert_eq!(
+            migration(
+                Dialect::Sqlite,
+                SchemaManagement::Create,
+                "logs",
+                None,
+                &columns
+            ),
+            [r#"CREATE TABLE IF NOT EXISTS "logs" ("id" INTEGER, "missing")"#]
+        );
+    }
+
+    #[test]
+    fn splits_qualified_names() {
+        assert_eq!(split_qualified("db.logs"), (Some("db"), "logs"));
+        assert_eq!(split_qualified("logs"), (None, "logs"));
+    }
+}
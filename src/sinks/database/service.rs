@@ -0,0 +1,282 @@
+use std::num::NonZeroUsize;
+
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use sqlx::{
+    Acquire, Database, Encode, Executor, IntoArguments, Pool, Transaction, Type, query::Query,
+};
+use vector_lib::{
+    EstimatedJsonEncodedSizeOf,
+    event::{Event, EventFinalizers, EventStatus, Finalizable},
+    request_metadata::{GroupedCountByteSize, MetaDescriptive, RequestMetadata},
+    stream::DriverResponse,
+};
+
+use super::{
+    ColumnMapping, Dialect, InsertStatement, MetricRows, MetricsConfig, PartialFailure, Rows,
+    SqlValue, dead_letter_insert, is_retriable,
+};
+use crate::{
+    internal_events::{DatabaseRowDeadLetteredError, DatabaseRowRejectedError},
+    sinks::prelude::RequestMetadataBuilder,
+};
+
+/// A batch of events to write into `table`.
+#[derive(Clone)]
+pub struct DatabaseRequest {
+    pub table: String,
+    pub events: Vec<Event>,
+    pub finalizers: EventFinalizers,
+    /// The finalizers of each event, shared with `finalizers`, to reject single rows with.
+    pub row_finalizers: Vec<EventFinalizers>,
+    pub metadata: RequestMetadata,
+}
+
+impl DatabaseRequest {
+    pub fn new(table: String, mut events: Vec<Event>) -> Result<Self, String> {
+        let row_finalizers = events
+            .iter_mut()
+            .map(Finalizable::take_finalizers)
+            .collect::<Vec<_>>();
+        let finalizers = row_finalizers.iter().cloned().collect();
+        let metadata_builder = RequestMetadataBuilder::from_events(&events);
+        let events_size = NonZeroUsize::new(events.estimated_json_encoded_size_of().get())
+            .ok_or("payload should never be zero length")?;
+        let metadata = metadata_builder.with_request_size(events_size);
+        Ok(DatabaseRequest {
+            table,
+            events,
+            finalizers,
+            row_finalizers,
+            metadata,
+        })
+    }
+
+    /// Converts the events into rows, through the logs built from them if `metrics` is set.
+    ///
+    /// Those logs are returned with the rows, to find the events of the rows that are rejected.
+    pub fn rows(
+        &self,
+        metrics: Option<&MetricsConfig>,
+        columns: Option<&ColumnMapping>,
+    ) -> (Rows, Option<MetricRows>) {
+        let metric_rows = metrics.map(|metrics| metrics.rows(&self.events));
+        let logs = match &metric_rows {
+            Some(metric_rows) => metric_rows.logs.iter().collect::<Vec<_>>(),
+            None => self.events.iter().filter_map(Event::maybe_as_log).collect(),
+        };
+        let rows = match columns {
+            Some(columns) => Rows::from_mapping(logs, columns),
+            None => Rows::from_logs(logs),
+        };
+        (rows, metric_rows)
+    }
+
+    /// Rejects the events that the `rejected` rows were built from.
+    pub fn reject_rows(&self, metric_rows: Option<&MetricRows>, rejected: Vec<usize>) {
+        let rejected = match metric_rows {
+            Some(metric_rows) => metric_rows.rejected_events(rejected),
+            None => rejected,
+        };
+        for index in rejected {
+            self.row_finalizers[index].update_status(EventStatus::Rejected);
+        }
+    }
+}
+
+impl Finalizable for DatabaseRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        self.finalizers.take_finalizers()
+    }
+}
+
+impl MetaDescriptive for DatabaseRequest {
+    fn get_metadata(&self) -> &RequestMetadata {
+        &self.metadata
+    }
+
+    fn metadata_mut(&mut self) -> &mut RequestMetadata {
+        &mut self.metadata
+    }
+}
+
+pub struct DatabaseResponse {
+    metadata: RequestMetadata,
+}
+
+impl DatabaseResponse {
+    pub const fn new(metadata: RequestMetadata) -> Self {
+        Self { metadata }
+    }
+}
+
+impl DriverResponse for DatabaseResponse {
+    fn event_status(&self) -> EventStatus {
+        EventStatus::Delivered
+    }
+
+    fn events_sent(&self) -> &GroupedCountByteSize {
+        self.metadata.events_estimated_json_encoded_byte_size()
+    }
+
+    fn bytes_sent(&self) -> Option<usize> {
+        Some(self.metadata.request_encoded_size())
+    }
+}
+
+/// A database whose driver can bind every [`SqlValue`] and execute statements on a connection.
+///
+/// It is implemented for every such `sqlx` database, so that the code writing rows only needs
+/// this bound rather than the driver's.
+pub trait RowDatabase: Database {
+    /// Executes `statement` on `connection`.
+    fn execute_statement(
+        connection: &mut Self::Connection,
+        statement: InsertStatement,
+    ) -> BoxFuture<'_, Result<(), sqlx::Error>>;
+}
+
+impl<DB> RowDatabase for DB
+where
+    DB: Database,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> DB::Arguments<'q>: IntoArguments<'q, DB>,
+    for<'q> bool: Encode<'q, DB> + Type<DB>,
+    for<'q> i64: Encode<'q, DB> + Type<DB>,
+    for<'q> f64: Encode<'q, DB> + Type<DB>,
+    for<'q> String: Encode<'q, DB> + Type<DB>,
+    for<'q> DateTime<Utc>: Encode<'q, DB> + Type<DB>,
+{
+    fn execute_statement(
+        connection: &mut DB::Connection,
+        statement: InsertStatement,
+    ) -> BoxFuture<'_, Result<(), sqlx::Error>> {
+        Box::pin(async move {
+            let query = statement
+                .params
+                .into_iter()
+                .fold(sqlx::query(&statement.sql), bind);
+            connection.execute(query).await?;
+            Ok(())
+        })
+    }
+}
+
+fn bind<'q, DB>(
+    query: Query<'q, DB, DB::Arguments<'q>>,
+    value: SqlValue,
+) -> Query<'q, DB, DB::Arguments<'q>>
+where
+    DB: Database,
+    bool: Encode<'q, DB> + Type<DB>,
+    i64: Encode<'q, DB> + Type<DB>,
+    f64: Encode<'q, DB> + Type<DB>,
+    String: Encode<'q, DB> + Type<DB>,
+    DateTime<Utc>: Encode<'q, DB> + Type<DB>,
+{
+    match value {
+        SqlValue::Null => query.bind(None::<String>),
+        SqlValue::Boolean(value) => query.bind(value),
+        SqlValue::Integer(value) => query.bind(value),
+        SqlValue::Float(value) => query.bind(value),
+        SqlValue::Text(value) | SqlValue::Json(value) => query.bind(value),
+        SqlValue::Timestamp(value) => query.bind(value),
+    }
+}
+
+/// Writes the rows of a batch into `table` in a single transaction, handling the rows that fail
+/// on their own as configured by `on_partial_failure`.
+pub struct RowWriter<'a, F> {
+    pub dialect: Dialect,
+    pub table: &'a str,
+    pub on_partial_failure: PartialFailure,
+    pub dead_letter_table: Option<&'a str>,
+    /// Renders the statements that write rows into `table`.
+    pub statements: F,
+}
+
+impl<F> RowWriter<'_, F>
+where
+    F: Fn(Rows) -> Vec<InsertStatement> + Sync,
+{
+    /// Writes `rows` with a connection of `pool`, returning the indexes of the rows that were
+    /// rejected on their own.
+    pub async fn write<DB: RowDatabase>(
+        &self,
+        pool: &Pool<DB>,
+        rows: Rows,
+    ) -> Result<Vec<usize>, sqlx::Error> {
+        let mut transaction = pool.begin().await?;
+        let mut rejected = Vec::new();
+        match self.on_partial_failure {
+            PartialFailure::RetryAll => self.execute::<DB>(&mut transaction, rows).await?,
+            PartialFailure::SplitBatch => {
+                self.insert_splitting(&mut transaction, rows, 0, &mut rejected)
+                    .await?
+            }
+        }
+        transaction.commit().await?;
+        Ok(rejected)
+    }
+
+    async fn execute<DB: RowDatabase>(
+        &self,
+        connection: &mut DB::Connection,
+        rows: Rows,
+    ) -> Result<(), sqlx::Error> {
+        for statement in (self.statements)(rows) {
+            DB::execute_statement(connection, statement).await?;
+        }
+        Ok(())
+    }
+
+    /// Inserts `rows` under a savepoint of `transaction`, splitting them in halves on errors
+    /// that are not transient, and collects the indexes of the rows that fail on their own into
+    /// `rejected`, counting from `offset`.
+    fn insert_splitting<'b, DB: RowDatabase>(
+        &'b self,
+        transaction: &'b mut Transaction<'static, DB>,
+        rows: Rows,
+        offset: usize,
+        rejected: &'b mut Vec<usize>,
+    ) -> BoxFuture<'b, Result<(), sqlx::Error>> {
+        Box::pin(async move {
+            let mut savepoint = transaction.begin().await?;
+            let error = match self.execute::<DB>(&mut savepoint, rows.clone()).await {
+                Ok(()) => return savepoint.commit().await,
+                Err(error) => error,
+            };
+            savepoint.rollback().await?;
+
+            if is_retriable(self.dialect, &error) {
+                return Err(error);
+            }
+            let table = self.table;
+            if rows.row_count() == 1 {
+                if let Some(dead_letter_table) = self.dead_letter_table {
+                    emit!(DatabaseRowDeadLetteredError {
+                        table,
+                        dead_letter_table,
+                        error: &error,
+                    });
+                    let statement =
+                        dead_letter_insert(self.dialect, dead_letter_table, table, &rows, &error);
+                    return DB::execute_statement(&mut **transaction, statement).await;
+                }
+                emit!(DatabaseRowRejectedError {
+                    table,
+                    error: &error,
+                });
+                rejected.push(offset);
+                return Ok(());
+            }
+
+            let (first, second) = rows.halve();
+            let second_offset = offset + first.row_count();
+            self.insert_splitting(transaction, first, offset, rejected)
+                .await?;
+            self.insert_splitting(transaction, second, second_offset, rejected)
+                .await
+        })
+    }
+}
@@ -0,0 +1,280 @@
+use std::{num::NonZeroU64, sync::Arc};
+
+use chrono::format::{Item, StrftimeItems};
+use futures::FutureExt;
+use indexmap::IndexMap;
+use tower::ServiceBuilder;
+use vector_lib::{
+    config::AcknowledgementsConfig,
+    configurable::{component::GenerateConfig, configurable_component},
+    sink::VectorSink,
+};
+
+use super::{
+    service::{SqliteRetryLogic, SqliteService, SqliteSettings},
+    sink::SqliteSink,
+};
+use crate::{
+    config::{Input, SinkConfig, SinkContext},
+    sinks::{
+        Healthcheck,
+        database::{
+            ColumnConfig, ColumnMapping, ConflictPolicy, DatabaseDefaultBatchSettings,
+            MetricsConfig, OnConflict, PartialFailure, SchemaManagement, TablePartitioner,
+            TimePartitionConfig,
+        },
+        util::{
+            BatchConfig, Concurrency, ServiceBuilderExt, TowerRequestConfig,
+            service::TowerRequestConfigDefaults,
+        },
+    },
+    template::Template,
+};
+
+const fn default_wal() -> bool {
+    true
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct SqliteTowerRequestConfigDefaults;
+
+impl TowerRequestConfigDefaults for SqliteTowerRequestConfigDefaults {
+    const CONCURRENCY: Concurrency = Concurrency::None;
+}
+
+/// Configuration for the `sqlite` sink.
+#[configurable_component(sink("sqlite", "Deliver log data to a local SQLite database file."))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SqliteConfig {
+    /// The path of the database file. The file and its parent directories are created if they
+    /// do not exist.
+    ///
+    /// The path can contain [`strftime` specifiers][strftime], which are rendered with the
+    /// current UTC time every time a batch is written. A new database file is opened whenever
+    /// the rendered path changes, which rotates the database by date.
+    ///
+    /// [strftime]: https://docs.rs/chrono/latest/chrono/format/strftime/index.html#specifiers
+    #[configurable(metadata(docs::examples = "/var/lib/vector/events.db"))]
+    #[configurable(metadata(docs::examples = "/var/lib/vector/events-%Y-%m-%d.db"))]
+    pub path: String,
+
+    /// The table that data is inserted into.
+    ///
+    /// The name can be qualified with an attached database, as in `main.table`, and can be a
+    /// template over event fields. Batches are partitioned by the rendered name, which is quoted
+    /// as an identifier when rendered into the `INSERT` statement. Events whose rendered name has
+    /// a different number of `.`-separated parts than the template are dropped.
+    #[configurable(metadata(docs::examples = "logs"))]
+    #[configurable(metadata(docs::examples = "logs_{{ service }}"))]
+    pub table: Template,
+
+    /// The tables that the sink may write to.
+    ///
+    /// When set, events whose rendered table is not in this list are dropped. This is
+    /// recommended when `table` is templated over untrusted event fields.
+    #[configurable(metadata(docs::examples = "logs_api"))]
+    pub allowed_tables: Option<Vec<String>>,
+
+    /// Writes events to one table per hour or day, named after `table` with the time of the
+    /// event timestamp appended, such as `logs_20250101`.
+    ///
+    /// `allowed_tables` is checked against the table before the time is appended.
+    #[configurable(derived)]
+    pub time_partition: Option<TimePartitionConfig>,
+
+    /// The columns that are written, keyed by column name, in the order of the table.
+    ///
+    /// When set, only these columns are written and all other event fields are ignored, unless
+    /// `overflow_column` is set, so that every batch inserts the same columns. When unset, the
+    /// columns are the top-level fields of the events in each batch.
+    #[configurable(metadata(
+        docs::additional_props_description = "The configuration of a column."
+    ))]
+    #[serde(default)]
+    pub columns: IndexMap<String, ColumnConfig>,
+
+    /// A column that all event fields not written to any of the `columns` are collected into,
+    /// as a JSON object.
+    ///
+    /// This keeps fields that were added to the events after the columns were configured. The
+    /// column is written as `NULL` for events whose fields are all mapped to columns.
+    #[configurable(metadata(docs::examples = "extra"))]
+    pub overflow_column: Option<String>,
+
+    /// How metric events are written as rows.
+    ///
+    /// When set, the sink accepts metrics instead of logs. Each metric is converted into a row
+    /// whose fields are then written as configured by `columns`.
+    #[configurable(derived)]
+    pub metrics: Option<MetricsConfig>,
+
+    /// Whether tables are created, and columns added, to fit the events that are written.
+    ///
+    /// Column types are inferred from the first non-null value of a field in a batch. Tables
+    /// and columns are only looked up the first time a batch needs them, so changes made to the
+    /// table outside of Vector are not picked up until the sink is restarted.
+    #[configurable(derived)]
+    #[serde(default)]
+    pub schema_management: SchemaManagement,
+
+    /// How rows that conflict with an existing row are handled.
+    #[configurable(derived)]
+    #[serde(default)]
+    pub conflict: ConflictPolicy,
+
+    /// The columns of the primary key or unique index that identifies a row.
+    ///
+    /// Required when `conflict` is `ignore` or `upsert`. They are the [conflict target][upsert]
+    /// of the statement, and are left unchanged by an upsert.
+    ///
+    /// [upsert]: https://www.sqlite.org/lang_upsert.html
+    #[configurable(metadata(docs::examples = "id"))]
+    #[serde(default)]
+    pub key_columns: Vec<String>,
+
+    /// How a batch is handled when some of its rows fail to insert.
+    #[configurable(derived)]
+    #[serde(default)]
+    pub on_partial_failure: PartialFailure,
+
+    /// A table that rows which fail to insert on their own are written to, instead of being
+    /// rejected.
+    ///
+    /// Requires `on_partial_failure` to be `split_batch`. Each row is written as a JSON object of
+    /// its columns to the `row` column, together with the table it failed to insert into, the
+    /// error, and the time it failed, in the `table`, `error`, and `timestamp` columns. The table
+    /// is created with these columns unless `schema_management` is `none`.
+    #[configurable(metadata(docs::examples = "failed_rows"))]
+    pub dead_letter_table: Option<String>,
+
+    /// SQL statements executed every time a database file is opened.
+    ///
+    /// This is typically used to create the table, with `CREATE TABLE IF NOT EXISTS`, so that
+    /// newly rotated files can be written to.
+    #[configurable(metadata(
+        docs::examples = "CREATE TABLE IF NOT EXISTS logs (timestamp TEXT, host TEXT, message TEXT)"
+    ))]
+    pub init_sql: Option<String>,
+
+    /// The size, in bytes, at which the database file is rotated.
+    ///
+    /// Once a write leaves the file (including its write-ahead log) at or above this size, the
+    /// file is closed and renamed with a UTC timestamp suffix, as in
+    /// `events.20250101T120000000000Z.db`, and the next batch is written to a new file.
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    pub max_file_size: Option<NonZeroU64>,
+
+    /// Whether to open the database in [write-ahead logging][wal] mode.
+    ///
+    /// [wal]: https://www.sqlite.org/wal.html
+    #[serde(default = "default_wal")]
+    pub wal: bool,
+
+    /// Event batching behavior.
+    ///
+    /// Each batch is written with multi-row `INSERT` statements whose columns are the union of
+    /// the top-level fields of the events in the batch, split to fit the placeholder limit of
+    /// the database, and all of them are executed in a single transaction. Fields that an event
+    /// does not set are written as `NULL`.
+    #[configurable(derived)]
+    #[serde(default)]
+    pub batch: BatchConfig<DatabaseDefaultBatchSettings>,
+
+    /// Outbound request behavior.
+    ///
+    /// SQLite allows a single writer per database, so batches are written one at a time
+    /// whatever the concurrency, and it defaults to `none`.
+    #[configurable(derived)]
+    #[serde(default)]
+    pub request: TowerRequestConfig<SqliteTowerRequestConfigDefaults>,
+
+    #[configurable(derived)]
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::is_default"
+    )]
+    pub acknowledgements: AcknowledgementsConfig,
+}
+
+impl GenerateConfig for SqliteConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"path = "/var/lib/vector/events.db"
+            table = "logs"
+            init_sql = "CREATE TABLE IF NOT EXISTS logs (timestamp TEXT, host TEXT, message TEXT)"
+        "#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "sqlite")]
+impl SinkConfig for SqliteConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        if StrftimeItems::new(&self.path).any(|item| matches!(item, Item::Error)) {
+            return Err(format!("Invalid strftime specifier in path {:?}.", self.path).into());
+        }
+        if self.dead_letter_table.is_some() && self.on_partial_failure != PartialFailure::SplitBatch
+        {
+            return Err(
+                "`dead_letter_table` requires `on_partial_failure` to be `split_batch`.".into(),
+            );
+        }
+        if let Some(metrics) = &self.metrics {
+            metrics.validate()?;
+        }
+
+        let columns = ColumnMapping::new(
+            &self.columns,
+            self.overflow_column.clone(),
+            cx.globals.timezone(),
+        )?
+        .map(Arc::new);
+        let service = SqliteService::new(SqliteSettings {
+            path: self.path.clone(),
+            on_conflict: OnConflict::new(self.conflict, self.key_columns.clone())?,
+            on_partial_failure: self.on_partial_failure,
+            dead_letter_table: self.dead_letter_table.clone(),
+            schema_management: self.schema_management,
+            time_partition: self.time_partition,
+            metrics: self.metrics.clone(),
+            columns: columns.clone(),
+            init_sql: self.init_sql.clone(),
+            max_file_size: self.max_file_size.map(NonZeroU64::get),
+            wal: self.wal,
+        });
+
+        let healthcheck = service.clone().healthcheck(self.table.clone()).boxed();
+
+        let batch_settings = self.batch.into_batcher_settings()?;
+        let request_settings = self.request.into_settings();
+
+        let service = ServiceBuilder::new()
+            .settings(request_settings, SqliteRetryLogic)
+            .service(service);
+
+        let partitioner = TablePartitioner::new(
+            self.table.clone(),
+            self.allowed_tables.clone(),
+            self.time_partition.as_ref(),
+        );
+        let sink = SqliteSink::new(service, partitioner, columns, batch_settings);
+
+        Ok((VectorSink::from_event_streamsink(sink), healthcheck))
+    }
+
+    fn input(&self) -> Input {
+        if self.metrics.is_some() {
+            Input::metric()
+        } else {
+            Input::log()
+        }
+    }
+
+    fn acknowledgements(&self) -> &AcknowledgementsConfig {
+        &self.acknowledgements
+    }
+}
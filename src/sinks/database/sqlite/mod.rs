@@ -0,0 +1,13 @@
+//! The SQLite [`vector_lib::sink::VectorSink`].
+//!
+//! Events are inserted as rows into a table of a local SQLite database file, one multi-row
+//! `INSERT` per batch. The file can be rotated by date, through `strftime` specifiers in its
+//! path, and by size.
+
+mod config;
+mod service;
+mod sink;
+#[cfg(test)]
+mod tests;
+
+pub use self::config::SqliteConfig;
@@ -0,0 +1,479 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use snafu::{ResultExt, Snafu};
+use sqlx::{
+    Pool, Sqlite,
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions},
+};
+use tokio::sync::Mutex;
+use tower::Service;
+
+use crate::{
+    internal_events::EndpointBytesSent,
+    sinks::{
+        database::{
+            ColumnDefinition, ColumnMapping, DatabaseRequest, DatabaseResponse, Dialect,
+            InsertStatement, MetricsConfig, OnConflict, PartialFailure, RowWriter, Rows,
+            SchemaManagement, TimePartitionConfig, check_table, dead_letter_columns, insert_probe,
+            is_retriable, migration, split_qualified,
+        },
+        prelude::RetryLogic,
+    },
+    template::Template,
+};
+
+const SQLITE_PROTOCOL: &str = "sqlite";
+
+#[derive(Clone)]
+pub struct SqliteRetryLogic;
+
+impl RetryLogic for SqliteRetryLogic {
+    type Error = SqliteServiceError;
+    type Request = DatabaseRequest;
+    type Response = DatabaseResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        match error {
+            SqliteServiceError::Sqlite { source } => is_retriable(Dialect::Sqlite, source),
+            SqliteServiceError::Io { .. } => true,
+        }
+    }
+}
+
+pub struct SqliteSettings {
+    pub path: String,
+    pub on_conflict: OnConflict,
+    pub on_partial_failure: PartialFailure,
+    pub dead_letter_table: Option<String>,
+    pub schema_management: SchemaManagement,
+    pub time_partition: Option<TimePartitionConfig>,
+    pub metrics: Option<MetricsConfig>,
+    pub columns: Option<Arc<ColumnMapping>>,
+    pub init_sql: Option<String>,
+    pub max_file_size: Option<u64>,
+    pub wal: bool,
+}
+
+impl SqliteSettings {
+    /// The path of the database file that should currently be written to.
+    fn current_path(&self) -> PathBuf {
+        PathBuf::from(Utc::now().format(&self.path).to_string())
+    }
+}
+
+/// A database file that is open for writing.
+struct OpenDatabase {
+    path: PathBuf,
+    pool: Pool<Sqlite>,
+    /// The columns of the tables that have been created or altered to fit a batch.
+    known_tables: HashMap<String, HashSet<String>>,
+    /// The partitions whose following partitions have been created.
+    created_ahead: HashSet<String>,
+}
+
+impl OpenDatabase {
+    async fn open(settings: &SqliteSettings, path: PathBuf) -> Result<Self, SqliteServiceError> {
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            tokio::fs::create_dir_all(parent).await.context(IoSnafu)?;
+        }
+
+        let journal_mode = if settings.wal {
+            SqliteJournalMode::Wal
+        } else {
+            SqliteJournalMode::Delete
+        };
+        let options = SqliteConnectOptions::new()
+            .filename(&path)
+            .create_if_missing(true)
+            .journal_mode(journal_mode);
+        // SQLite only allows a single writer, so there is nothing to gain from more connections.
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .context(SqliteSnafu)?;
+
+        if let Some(init_sql) = &settings.init_sql {
+            sqlx::raw_sql(init_sql)
+                .execute(&pool)
+                .await
+                .context(SqliteSnafu)?;
+        }
+
+        Ok(Self {
+            path,
+            pool,
+            known_tables: HashMap::new(),
+            created_ahead: HashSet::new(),
+        })
+    }
+
+    /// Inserts `rows` into `table` in a single transaction, returning the indexes of the rows
+    /// that were rejected on their own.
+    async fn insert(
+        &mut self,
+        settings: &SqliteSettings,
+        table: &str,
+        rows: Rows,
+    ) -> Result<Vec<usize>, SqliteServiceError> {
+        let columns = rows.column_definitions(Dialect::Sqlite, settings.columns.as_deref());
+        self.prepare_table(settings.schema_management, table, columns)
+            .await?;
+        self.create_ahead(settings, table).await?;
+        if let Some(dead_letter_table) = &settings.dead_letter_table {
+            self.prepare_table(
+                settings.schema_management,
+                dead_letter_table,
+                dead_letter_columns(Dialect::Sqlite),
+            )
+            .await?;
+        }
+
+        let writer = RowWriter {
+            dialect: Dialect::Sqlite,
+            table,
+            on_partial_failure: settings.on_partial_failure,
+            dead_letter_table: settings.dead_letter_table.as_deref(),
+            statements: |rows: Rows| {
+                InsertStatement::chunked(Dialect::Sqlite, table, rows, &settings.on_conflict)
+            },
+        };
+        writer.write(&self.pool, rows).await.context(SqliteSnafu)
+    }
+
+    /// Creates or alters `table` as configured, so that `columns` can be inserted into it.
+    async fn prepare_table(
+        &mut self,
+        management: SchemaManagement,
+        table: &str,
+        columns: Vec<ColumnDefinition>,
+    ) -> Result<(), SqliteServiceError> {
+        if management == SchemaManagement::None {
+            return Ok(());
+        }
+
+        if let Some(known) = self.known_tables.get(table)
+            && (management == SchemaManagement::Create
+                || columns.iter().all(|column| known.contains(&column.name)))
+        {
+            return Ok(());
+        }
+
+        // Creating the table is idempotent, so only altering it requires knowing its columns.
+        let existing = match management {
+            SchemaManagement::CreateAndAlter => self.table_columns(table).await?,
+            _ => None,
+        };
+        for statement in migration(
+            Dialect::Sqlite,
+            management,
+            table,
+            existing.as_ref(),
+            &columns,
+        ) {
+            sqlx::query(&statement)
+                .execute(&self.pool)
+                .await
+                .context(SqliteSnafu)?;
+        }
+
+        let mut known = existing.unwrap_or_default();
+        known.extend(columns.into_iter().map(|column| column.name));
+        self.known_tables.insert(table.to_owned(), known);
+        Ok(())
+    }
+
+    /// Creates the partitions following `table` with its columns, the first time it is written
+    /// to.
+    ///
+    /// SQLite cannot create a table like another one, so the constraints and indexes of
+    /// `table` are not copied.
+    async fn create_ahead(
+        &mut self,
+        settings: &SqliteSettings,
+        table: &str,
+    ) -> Result<(), SqliteServiceError> {
+        let Some(time_partition) = &settings.time_partition else {
+            return Ok(());
+        };
+        if self.created_ahead.contains(table) {
+            return Ok(());
+        }
+
+        let following = time_partition.following(table);
+        if !following.is_empty() {
+            let columns = self.column_definitions(table).await?;
+            // The table does not exist yet, so there is nothing to create the partitions from.
+            if columns.is_empty() {
+                return Ok(());
+            }
+            for next in following {
+                for statement in migration(
+                    Dialect::Sqlite,
+                    SchemaManagement::Create,
+                    &next,
+                    None,
+                    &columns,
+                ) {
+                    sqlx::query(&statement)
+                        .execute(&self.pool)
+                        .await
+                        .context(SqliteSnafu)?;
+                }
+            }
+        }
+        self.created_ahead.insert(table.to_owned());
+        Ok(())
+    }
+
+    /// The columns of `table` as they were declared.
+    async fn column_definitions(
+        &self,
+        table: &str,
+    ) -> Result<Vec<ColumnDefinition>, SqliteServiceError> {
+        let columns: Vec<(String, String, bool)> = match split_qualified(table) {
+            (Some(schema), name) => {
+                sqlx::query_as(r#"SELECT name, type, "notnull" FROM pragma_table_info(?, ?)"#)
+                    .bind(name)
+                    .bind(schema)
+            }
+            (None, name) => {
+                sqlx::query_as(r#"SELECT name, type, "notnull" FROM pragma_table_info(?)"#)
+                    .bind(name)
+            }
+        }
+        .fetch_all(&self.pool)
+        .await
+        .context(SqliteSnafu)?;
+
+        Ok(columns
+            .into_iter()
+            .map(|(name, sql_type, not_null)| ColumnDefinition {
+                name,
+                sql_type,
+                nullable: !not_null,
+            })
+            .collect())
+    }
+
+    /// The columns of `table`, or `None` if it does not exist.
+    async fn table_columns(
+        &self,
+        table: &str,
+    ) -> Result<Option<HashSet<String>>, SqliteServiceError> {
+        let columns: Vec<String> = match split_qualified(table) {
+            (Some(schema), name) => sqlx::query_scalar("SELECT name FROM pragma_table_info(?, ?)")
+                .bind(name)
+                .bind(schema),
+            (None, name) => sqlx::query_scalar("SELECT name FROM pragma_table_info(?)").bind(name),
+        }
+        .fetch_all(&self.pool)
+        .await
+        .context(SqliteSnafu)?;
+
+        Ok((!columns.is_empty()).then(|| columns.into_iter().collect()))
+    }
+
+    /// The size of the database file, including its write-ahead log.
+    async fn size(&self) -> io::Result<u64> {
+        let mut size = tokio::fs::metadata(&self.path).await?.len();
+        match tokio::fs::metadata(wal_path(&self.path)).await {
+            Ok(metadata) => size += metadata.len(),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+            Err(error) => return Err(error),
+        }
+        Ok(size)
+    }
+
+    /// Closes the database and moves it out of the way of the next one.
+    async fn rotate(self) -> io::Result<()> {
+        // Closing the last connection checkpoints the write-ahead log into the database file.
+        self.pool.close().await;
+        tokio::fs::rename(&self.path, rotated_path(&self.path, Utc::now())).await
+    }
+}
+
+fn wal_path(path: &Path) -> PathBuf {
+    let mut wal_path = path.as_os_str().to_owned();
+    wal_path.push("-wal");
+    wal_path.into()
+}
+
+/// Inserts a UTC timestamp between the file stem and extension of `path`.
+fn rotated_path(path: &Path, timestamp: DateTime<Utc>) -> PathBuf {
+    let timestamp = timestamp.format("%Y%m%dT%H%M%S%6fZ");
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let file_name = match path.extension() {
+        Some(extension) => format!("{stem}.{timestamp}.{}", extension.to_string_lossy()),
+        None => format!("{stem}.{timestamp}"),
+    };
+    path.with_file_name(file_name)
+}
+
+#[derive(Clone)]
+pub struct SqliteService {
+    settings: Arc<SqliteSettings>,
+    database: Arc<Mutex<Option<OpenDatabase>>>,
+}
+
+impl SqliteService {
+    pub fn new(settings: SqliteSettings) -> Self {
+        Self {
+            settings: Arc::new(settings),
+            database: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Opens the database, and checks that the table can be inserted into if it is not
+    /// templated, creating the table from the configured columns first if needed.
+    pub async fn healthcheck(self, table: Template) -> crate::Result<()> {
+        let mut current = self.database.lock().await;
+        if current.is_none() {
+            let path = self.settings.current_path();
+            *current = Some(OpenDatabase::open(&self.settings, path).await?);
+        }
+
+        let partitioned = table.is_dynamic() || self.settings.time_partition.is_some();
+        let Some(database) = current.as_mut().filter(|_| !partitioned) else {
+            return Ok(());
+        };
+        let table = table.get_ref();
+        let columns = self.settings.columns.as_deref();
+
+        if let Some(columns) = columns {
+            database
+                .prepare_table(
+                    self.settings.schema_management,
+                    table,
+                    columns.definitions(Dialect::Sqlite),
+                )
+                .await?;
+        }
+
+        let existing = database.table_columns(table).await?;
+        check_table(
+            table,
+            self.settings.schema_management,
+            existing.as_ref(),
+            columns,
+        )?;
+        // SQLite has no privileges, but this fails if the database can only be read.
+        if existing.is_some() {
+            sqlx::query(&insert_probe(Dialect::Sqlite, table, columns))
+                .execute(&database.pool)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn write(&self, table: &str, rows: Rows) -> Result<Vec<usize>, SqliteServiceError> {
+        let mut current = self.database.lock().await;
+
+        let path = self.settings.current_path();
+        let mut database = match current.take() {
+            Some(database) if database.path == path => database,
+            previous => {
+                if let Some(previous) = previous {
+                    previous.pool.close().await;
+                }
+                OpenDatabase::open(&self.settings, path).await?
+            }
+        };
+
+        let result = database.insert(&self.settings, table, rows).await;
+
+        // The batch has been committed, so failing to rotate the file must not fail the request,
+        // which would insert the batch again when it is retried.
+        let rotate = match self.settings.max_file_size {
+            Some(max_file_size) if result.is_ok() => match database.size().await {
+                Ok(size) => size >= max_file_size,
+                Err(error) => {
+                    error!(message = "Failed to read the size of the database file.", %error);
+                    false
+                }
+            },
+            _ => false,
+        };
+        if rotate {
+            // The database is closed either way, so the next batch opens the file again.
+            if let Err(error) = database.rotate().await {
+                error!(message = "Failed to rotate the database file.", %error);
+            }
+        } else {
+            *current = Some(database);
+        }
+
+        result
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum SqliteServiceError {
+    #[snafu(display("Database error: {source}"))]
+    Sqlite { source: sqlx::Error },
+
+    #[snafu(display("Database file error: {source}"))]
+    Io { source: io::Error },
+}
+
+impl Service<DatabaseRequest> for SqliteService {
+    type Response = DatabaseResponse;
+    type Error = SqliteServiceError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: DatabaseRequest) -> Self::Future {
+        let service = self.clone();
+        let future = async move {
+            let settings = &service.settings;
+            let (rows, metric_rows) =
+                request.rows(settings.metrics.as_ref(), settings.columns.as_deref());
+            let rejected = service.write(&request.table, rows).await?;
+            request.reject_rows(metric_rows.as_ref(), rejected);
+
+            emit!(EndpointBytesSent {
+                byte_size: request.metadata.request_encoded_size(),
+                protocol: SQLITE_PROTOCOL,
+                endpoint: &settings.path,
+            });
+
+            Ok(DatabaseResponse::new(request.metadata))
+        };
+
+        Box::pin(future)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn rotated_path_keeps_extension() {
+        let timestamp = Utc.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap();
+        assert_eq!(
+            rotated_path(Path::new("/data/events.db"), timestamp),
+            PathBuf::from("/data/events.20250102T030405000000Z.db")
+        );
+        assert_eq!(
+            rotated_path(Path::new("/data/events"), timestamp),
+            PathBuf::from("/data/events.20250102T030405000000Z")
+        );
+    }
+}
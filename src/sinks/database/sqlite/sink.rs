@@ -0,0 +1,68 @@
+use std::{future::ready, sync::Arc};
+
+use super::service::{SqliteRetryLogic, SqliteService};
+use crate::sinks::{
+    database::{ColumnMapping, DatabaseRequest, TablePartitioner},
+    prelude::*,
+};
+
+pub struct SqliteSink {
+    service: Svc<SqliteService, SqliteRetryLogic>,
+    partitioner: TablePartitioner,
+    columns: Option<Arc<ColumnMapping>>,
+    batch_settings: BatcherSettings,
+}
+
+impl SqliteSink {
+    pub const fn new(
+        service: Svc<SqliteService, SqliteRetryLogic>,
+        partitioner: TablePartitioner,
+        columns: Option<Arc<ColumnMapping>>,
+        batch_settings: BatcherSettings,
+    ) -> Self {
+        Self {
+            service,
+            partitioner,
+            columns,
+            batch_settings,
+        }
+    }
+
+    async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let batch_settings = self.batch_settings;
+        let columns = self.columns;
+
+        input
+            .filter_map(move |event| {
+                ready(match &columns {
+                    Some(columns) => columns.check(event),
+                    None => Some(event),
+                })
+            })
+            .batched_partitioned(self.partitioner, batch_settings.timeout, |_| {
+                batch_settings.as_byte_size_config()
+            })
+            .filter_map(|(table, events)| async move {
+                match DatabaseRequest::new(table?, events) {
+                    Ok(request) => Some(request),
+                    Err(e) => {
+                        warn!(
+                            message = "Error creating sqlite sink's request.",
+                            error = %e
+                        );
+                        None
+                    }
+                }
+            })
+            .into_driver(self.service)
+            .run()
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl StreamSink<Event> for SqliteSink {
+    async fn run(mut self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.run_inner(input).await
+    }
+}
@@ -0,0 +1,499 @@
+use std::path::Path;
+
+use chrono::TimeZone;
+use futures::stream;
+use sqlx::{Connection, SqliteConnection, sqlite::SqliteConnectOptions};
+use vector_lib::event::{
+    BatchNotifier, BatchStatus, Event, LogEvent, Metric, MetricKind, MetricValue,
+};
+
+use super::SqliteConfig;
+use crate::{
+    config::{SinkConfig, SinkContext},
+    test_util::{
+        components::{
+            COMPONENT_ERROR_TAGS, run_and_assert_sink_compliance, run_and_assert_sink_error,
+        },
+        temp_dir, trace_init,
+    },
+};
+
+const SQLITE_SINK_TAGS: [&str; 2] = ["endpoint", "protocol"];
+
+const CREATE_TABLE: &str =
+    "CREATE TABLE IF NOT EXISTS logs (id INTEGER, host TEXT, message TEXT, timestamp TEXT)";
+
+fn config(path: &Path, extra: &str) -> SqliteConfig {
+    toml::from_str(&format!(
+        r#"
+            path = "{}"
+            table = "logs"
+            init_sql = "{CREATE_TABLE}"
+            {extra}
+        "#,
+        path.display()
+    ))
+    .unwrap()
+}
+
+fn create_event(id: i64) -> Event {
+    let mut event = LogEvent::from("raw log line");
+    event.insert("id", id);
+    event.insert("host", "example.com");
+    event.into()
+}
+
+async fn select_ids(path: &Path) -> Vec<i64> {
+    let mut connection =
+        SqliteConnection::connect_with(&SqliteConnectOptions::new().filename(path))
+            .await
+            .unwrap();
+    sqlx::query_scalar("SELECT id FROM logs ORDER BY id")
+        .fetch_all(&mut connection)
+        .await
+        .unwrap()
+}
+
+#[test]
+fn generate_config() {
+    crate::test_util::test_generate_config::<SqliteConfig>();
+}
+
+#[tokio::test]
+async fn invalid_path_specifier_fails_build() {
+    let config = config(Path::new("/tmp/events-%Q.db"), "");
+    assert!(config.build(SinkContext::default()).await.is_err());
+}
+
+#[tokio::test]
+async fn healthcheck_creates_database() {
+    trace_init();
+
+    let path = temp_dir().join("nested").join("events.db");
+    let (_sink, healthcheck) = config(&path, "")
+        .build(SinkContext::default())
+        .await
+        .unwrap();
+    healthcheck.await.unwrap();
+
+    assert!(path.exists());
+    assert!(select_ids(&path).await.is_empty());
+}
+
+#[tokio::test]
+async fn healthcheck_checks_table() {
+    trace_init();
+
+    let path = temp_dir().join("events.db");
+    let missing_table = toml::from_str::<SqliteConfig>(&format!(
+        r#"
+            path = "{}"
+            table = "logs"
+        "#,
+        path.display()
+    ))
+    .unwrap();
+    let (_sink, healthcheck) = missing_table.build(SinkContext::default()).await.unwrap();
+    assert!(healthcheck.await.is_err());
+
+    let missing_column = config(&path, "columns.level = {}");
+    let (_sink, healthcheck) = missing_column.build(SinkContext::default()).await.unwrap();
+    assert!(healthcheck.await.is_err());
+}
+
+#[tokio::test]
+async fn insert_multiple_events() {
+    trace_init();
+
+    let path = temp_dir().join("events.db");
+    let (sink, _healthcheck) = config(&path, "")
+        .build(SinkContext::default())
+        .await
+        .unwrap();
+
+    let mut events = (0..10).map(create_event).collect::<Vec<_>>();
+    let mut receiver = BatchNotifier::apply_to(&mut events);
+    run_and_assert_sink_compliance(sink, stream::iter(events), &SQLITE_SINK_TAGS).await;
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    assert_eq!(select_ids(&path).await, (0..10).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn splits_batches_exceeding_placeholder_limit() {
+    trace_init();
+
+    // Every event binds four values, so a single statement could hold at most 8191 of them.
+    let path = temp_dir().join("events.db");
+    let (sink, _healthcheck) = config(
+        &path,
+        "batch.max_events = 10000\nbatch.max_bytes = 100000000",
+    )
+    .build(SinkContext::default())
+    .await
+    .unwrap();
+
+    let events = (0..10_000).map(create_event).collect::<Vec<_>>();
+    run_and_assert_sink_compliance(sink, stream::iter(events), &SQLITE_SINK_TAGS).await;
+
+    assert_eq!(select_ids(&path).await, (0..10_000).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn upsert_updates_existing_rows() {
+    trace_init();
+
+    let path = temp_dir().join("events.db");
+    let config = toml::from_str::<SqliteConfig>(&format!(
+        r#"
+            path = "{}"
+            table = "logs"
+            init_sql = "CREATE TABLE IF NOT EXISTS logs (id INTEGER PRIMARY KEY, message TEXT, timestamp TEXT)"
+            conflict = "upsert"
+            key_columns = ["id"]
+        "#,
+        path.display()
+    ))
+    .unwrap();
+
+    for message in ["first", "second"] {
+        let (sink, _healthcheck) = config.build(SinkContext::default()).await.unwrap();
+        let mut event = LogEvent::from(message);
+        event.insert("id", 1);
+        let events = vec![Event::from(event)];
+        run_and_assert_sink_compliance(sink, stream::iter(events), &SQLITE_SINK_TAGS).await;
+    }
+
+    let mut connection =
+        SqliteConnection::connect_with(&SqliteConnectOptions::new().filename(&path))
+            .await
+            .unwrap();
+    let messages: Vec<String> = sqlx::query_scalar("SELECT message FROM logs")
+        .fetch_all(&mut connection)
+        .await
+        .unwrap();
+    assert_eq!(messages, ["second"]);
+}
+
+#[tokio::test]
+async fn creates_and_alters_tables() {
+    trace_init();
+
+    let path = temp_dir().join("events.db");
+    let config = toml::from_str::<SqliteConfig>(&format!(
+        r#"
+            path = "{}"
+            table = "logs"
+            schema_management = "create_and_alter"
+        "#,
+        path.display()
+    ))
+    .unwrap();
+
+    let mut extended = LogEvent::from("raw log line");
+    extended.insert("id", 1);
+    extended.insert("level", "info");
+
+    // The first batch creates the table, and the second one adds the column it is missing.
+    for event in [create_event(0), extended.into()] {
+        let (sink, _healthcheck) = config.build(SinkContext::default()).await.unwrap();
+        run_and_assert_sink_compliance(sink, stream::iter(vec![event]), &SQLITE_SINK_TAGS).await;
+    }
+
+    let mut connection =
+        SqliteConnection::connect_with(&SqliteConnectOptions::new().filename(&path))
+            .await
+            .unwrap();
+    let columns: Vec<String> = sqlx::query_scalar("SELECT name FROM pragma_table_info('logs')")
+        .fetch_all(&mut connection)
+        .await
+        .unwrap();
+    assert_eq!(columns, ["host", "id", "message", "timestamp", "level"]);
+    assert_eq!(select_ids(&path).await, vec![0, 1]);
+}
+
+#[tokio::test]
+async fn rotates_by_size() {
+    trace_init();
+
+    let dir = temp_dir();
+    let path = dir.join("events.db");
+    let (sink, _healthcheck) = config(&path, "max_file_size = 1\nbatch.max_events = 1")
+        .build(SinkContext::default())
+        .await
+        .unwrap();
+
+    let events = (0..2).map(create_event).collect::<Vec<_>>();
+    run_and_assert_sink_compliance(sink, stream::iter(events), &SQLITE_SINK_TAGS).await;
+
+    // Every batch exceeds the limit, so each one ends up in its own rotated file.
+    assert!(!path.exists());
+    let mut rotated = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "db"))
+        .collect::<Vec<_>>();
+    rotated.sort();
+    assert_eq!(rotated.len(), 2);
+    assert_eq!(select_ids(&rotated[0]).await, vec![0]);
+    assert_eq!(select_ids(&rotated[1]).await, vec![1]);
+}
+
+#[tokio::test]
+async fn insertion_fails_unknown_column() {
+    trace_init();
+
+    let path = temp_dir().join("events.db");
+    let config = toml::from_str::<SqliteConfig>(&format!(
+        r#"
+            path = "{}"
+            table = "logs"
+            init_sql = "CREATE TABLE IF NOT EXISTS logs (message TEXT)"
+        "#,
+        path.display()
+    ))
+    .unwrap();
+    let (sink, _healthcheck) = config.build(SinkContext::default()).await.unwrap();
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let event = create_event(0).with_batch_notifier(&batch);
+    drop(batch);
+    run_and_assert_sink_error(sink, stream::iter(vec![event]), &COMPONENT_ERROR_TAGS).await;
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Rejected));
+}
+
+#[tokio::test]
+async fn writes_configured_columns() {
+    trace_init();
+
+    let path = temp_dir().join("events.db");
+    let config = toml::from_str::<SqliteConfig>(&format!(
+        r#"
+            path = "{}"
+            table = "logs"
+            schema_management = "create"
+            columns.id = {{ type = "INTEGER", nullable = false }}
+            columns.server = {{ field = "host" }}
+            columns.level = {{ default = "info" }}
+        "#,
+        path.display()
+    ))
+    .unwrap();
+    let (sink, healthcheck) = config.build(SinkContext::default()).await.unwrap();
+    healthcheck.await.unwrap();
+
+    // The event without an `id` is rejected on its own, and the rest of the batch is written.
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let events = vec![
+        create_event(0).with_batch_notifier(&batch),
+        Event::from(LogEvent::from("no id")).with_batch_notifier(&batch),
+    ];
+    drop(batch);
+    run_and_assert_sink_compliance(sink, stream::iter(events), &SQLITE_SINK_TAGS).await;
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Rejected));
+
+    let mut connection =
+        SqliteConnection::connect_with(&SqliteConnectOptions::new().filename(&path))
+            .await
+            .unwrap();
+    let rows: Vec<(i64, String, String)> = sqlx::query_as("SELECT * FROM logs")
+        .fetch_all(&mut connection)
+        .await
+        .unwrap();
+    assert_eq!(rows, [(0, "example.com".to_owned(), "info".to_owned())]);
+}
+
+#[tokio::test]
+async fn collects_unmapped_fields_into_overflow_column() {
+    trace_init();
+
+    let path = temp_dir().join("events.db");
+    let config = toml::from_str::<SqliteConfig>(&format!(
+        r#"
+            path = "{}"
+            table = "logs"
+            schema_management = "create"
+            columns.id = {{}}
+            overflow_column = "extra"
+        "#,
+        path.display()
+    ))
+    .unwrap();
+    let (sink, _healthcheck) = config.build(SinkContext::default()).await.unwrap();
+    run_and_assert_sink_compliance(sink, stream::iter(vec![create_event(0)]), &SQLITE_SINK_TAGS)
+        .await;
+
+    let mut connection =
+        SqliteConnection::connect_with(&SqliteConnectOptions::new().filename(&path))
+            .await
+            .unwrap();
+    let extra: String = sqlx::query_scalar("SELECT extra FROM logs")
+        .fetch_one(&mut connection)
+        .await
+        .unwrap();
+    let extra = serde_json::from_str::<serde_json::Value>(&extra).unwrap();
+    assert_eq!(extra["host"], "example.com");
+    assert_eq!(extra["message"], "raw log line");
+    assert!(extra.get("id").is_none());
+}
+
+#[tokio::test]
+async fn split_batch_rejects_only_failing_rows() {
+    trace_init();
+
+    let path = temp_dir().join("events.db");
+    let config = toml::from_str::<SqliteConfig>(&format!(
+        r#"
+            path = "{}"
+            table = "logs"
+            init_sql = "CREATE TABLE IF NOT EXISTS logs (id INTEGER CHECK (id <> 3), host TEXT, message TEXT, timestamp TEXT)"
+            on_partial_failure = "split_batch"
+        "#,
+        path.display()
+    ))
+    .unwrap();
+    let (sink, _healthcheck) = config.build(SinkContext::default()).await.unwrap();
+
+    let (events, receivers): (Vec<_>, Vec<_>) = (0..5)
+        .map(|id| {
+            let (batch, receiver) = BatchNotifier::new_with_receiver();
+            (create_event(id).with_batch_notifier(&batch), receiver)
+        })
+        .unzip();
+    run_and_assert_sink_compliance(sink, stream::iter(events), &SQLITE_SINK_TAGS).await;
+
+    let statuses = receivers
+        .into_iter()
+        .map(|mut receiver| receiver.try_recv().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        statuses,
+        [
+            BatchStatus::Delivered,
+            BatchStatus::Delivered,
+            BatchStatus::Delivered,
+            BatchStatus::Rejected,
+            BatchStatus::Delivered,
+        ]
+    );
+    assert_eq!(select_ids(&path).await, vec![0, 1, 2, 4]);
+}
+
+#[tokio::test]
+async fn writes_failing_rows_to_dead_letter_table() {
+    trace_init();
+
+    let path = temp_dir().join("events.db");
+    let config = toml::from_str::<SqliteConfig>(&format!(
+        r#"
+            path = "{}"
+            table = "logs"
+            init_sql = "CREATE TABLE IF NOT EXISTS logs (id INTEGER CHECK (id <> 3), host TEXT, message TEXT, timestamp TEXT)"
+            on_partial_failure = "split_batch"
+            dead_letter_table = "failed_rows"
+            schema_management = "create"
+        "#,
+        path.display()
+    ))
+    .unwrap();
+    let (sink, _healthcheck) = config.build(SinkContext::default()).await.unwrap();
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let events = (0..5)
+        .map(|id| create_event(id).with_batch_notifier(&batch))
+        .collect::<Vec<_>>();
+    drop(batch);
+    run_and_assert_sink_compliance(sink, stream::iter(events), &SQLITE_SINK_TAGS).await;
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+    assert_eq!(select_ids(&path).await, vec![0, 1, 2, 4]);
+
+    let mut connection =
+        SqliteConnection::connect_with(&SqliteConnectOptions::new().filename(&path))
+            .await
+            .unwrap();
+    let failed: Vec<(String, i64)> =
+        sqlx::query_as("SELECT \"table\", row ->> '$.id' FROM failed_rows")
+            .fetch_all(&mut connection)
+            .await
+            .unwrap();
+    assert_eq!(failed, [("logs".to_owned(), 3)]);
+}
+
+#[tokio::test]
+async fn creates_following_partitions_ahead() {
+    trace_init();
+
+    let path = temp_dir().join("events.db");
+    let config = toml::from_str::<SqliteConfig>(&format!(
+        r#"
+            path = "{}"
+            table = "logs"
+            schema_management = "create"
+            time_partition.interval = "daily"
+            time_partition.create_ahead = 2
+        "#,
+        path.display()
+    ))
+    .unwrap();
+    let (sink, _healthcheck) = config.build(SinkContext::default()).await.unwrap();
+
+    let mut event = LogEvent::from("raw log line");
+    event.insert("id", 1);
+    event.insert(
+        "timestamp",
+        chrono::Utc
+            .with_ymd_and_hms(2025, 12, 31, 12, 0, 0)
+            .unwrap(),
+    );
+    run_and_assert_sink_compliance(sink, stream::iter(vec![event.into()]), &SQLITE_SINK_TAGS).await;
+
+    let mut connection =
+        SqliteConnection::connect_with(&SqliteConnectOptions::new().filename(&path))
+            .await
+            .unwrap();
+    let tables: Vec<String> =
+        sqlx::query_scalar("SELECT name FROM sqlite_master WHERE type = 'table' ORDER BY name")
+            .fetch_all(&mut connection)
+            .await
+            .unwrap();
+    assert_eq!(tables, ["logs_20251231", "logs_20260101", "logs_20260102"]);
+}
+
+#[tokio::test]
+async fn writes_wide_metric_rows() {
+    trace_init();
+
+    let path = temp_dir().join("events.db");
+    let config = toml::from_str::<SqliteConfig>(&format!(
+        r#"
+            path = "{}"
+            table = "metrics"
+            schema_management = "create"
+            metrics.layout = "wide"
+            metrics.columns.cpu = "cpu_usage"
+            metrics.columns.memory = "memory_usage"
+        "#,
+        path.display()
+    ))
+    .unwrap();
+    let (sink, _healthcheck) = config.build(SinkContext::default()).await.unwrap();
+
+    let events = [("cpu_usage", 0.5), ("memory_usage", 512.0)].map(|(name, value)| {
+        Event::from(Metric::new(
+            name,
+            MetricKind::Absolute,
+            MetricValue::Gauge { value },
+        ))
+    });
+    run_and_assert_sink_compliance(sink, stream::iter(events), &SQLITE_SINK_TAGS).await;
+
+    let mut connection =
+        SqliteConnection::connect_with(&SqliteConnectOptions::new().filename(&path))
+            .await
+            .unwrap();
+    let rows: Vec<(f64, f64)> = sqlx::query_as("SELECT cpu, memory FROM metrics")
+        .fetch_all(&mut connection)
+        .await
+        .unwrap();
+    assert_eq!(rows, [(0.5, 512.0)]);
+}
@@ -0,0 +1,114 @@
+use chrono::{NaiveDateTime, TimeDelta};
+use vector_lib::configurable::configurable_component;
+
+use crate::template::Template;
+
+/// Writes events to one table per hour or day, named after the table with a time suffix.
+#[configurable_component]
+#[derive(Clone, Copy, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct TimePartitionConfig {
+    /// The time span covered by each table.
+    #[configurable(derived)]
+    pub interval: PartitionInterval,
+
+    /// The number of following tables that are created from a table when it is first written
+    /// to, so that they exist before events start being written to them.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = 1))]
+    pub create_ahead: u32,
+}
+
+/// The time span covered by a table.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PartitionInterval {
+    /// One table per hour, such as `logs_2025010113`.
+    Hourly,
+
+    /// One table per day, such as `logs_20250101`.
+    Daily,
+}
+
+impl PartitionInterval {
+    const fn suffix(self) -> &'static str {
+        match self {
+            Self::Hourly => "%Y%m%d%H",
+            Self::Daily => "%Y%m%d",
+        }
+    }
+
+    const fn span(self) -> TimeDelta {
+        match self {
+            Self::Hourly => TimeDelta::hours(1),
+            Self::Daily => TimeDelta::days(1),
+        }
+    }
+
+    fn parse(self, suffix: &str) -> Option<NaiveDateTime> {
+        // Parsing needs minutes, and days also need an hour, to form a date and time.
+        let padded = match self {
+            Self::Hourly => format!("{suffix}00"),
+            Self::Daily => format!("{suffix}0000"),
+        };
+        NaiveDateTime::parse_from_str(&padded, "%Y%m%d%H%M").ok()
+    }
+}
+
+impl TimePartitionConfig {
+    /// The template of the suffix that is appended to the table, rendered from the event
+    /// timestamp.
+    pub fn suffix(&self) -> Template {
+        Template::try_from(self.interval.suffix()).expect("partition suffixes are valid templates")
+    }
+
+    /// The names of the `create_ahead` partitions that follow the partition `table`.
+    pub fn following(&self, table: &str) -> Vec<String> {
+        let Some((base, start)) = table
+            .rsplit_once('_')
+            .and_then(|(base, suffix)| Some((base, self.interval.parse(suffix)?)))
+        else {
+            return Vec::new();
+        };
+
+        (1..=self.create_ahead)
+            .filter_map(|index| start.checked_add_signed(self.interval.span() * index as i32))
+            .map(|time| format!("{base}_{}", time.format(self.interval.suffix())))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn partition(interval: PartitionInterval, create_ahead: u32) -> TimePartitionConfig {
+        TimePartitionConfig {
+            interval,
+            create_ahead,
+        }
+    }
+
+    #[test]
+    fn names_following_partitions() {
+        assert_eq!(
+            partition(PartitionInterval::Daily, 2).following("db.logs_20251231"),
+            ["db.logs_20260101", "db.logs_20260102"]
+        );
+        assert_eq!(
+            partition(PartitionInterval::Hourly, 1).following("logs_2025010123"),
+            ["logs_2025010200"]
+        );
+        assert!(
+            partition(PartitionInterval::Daily, 1)
+                .following("logs")
+                .is_empty()
+        );
+        assert!(
+            partition(PartitionInterval::Daily, 0)
+                .following("logs_20250101")
+                .is_empty()
+        );
+    }
+}
@@ -38,6 +38,12 @@ pub mod blackhole;
 pub mod clickhouse;
 #[cfg(feature = "sinks-console")]
 pub mod console;
+#[cfg(any(
+    feature = "sinks-mysql",
+    feature = "sinks-postgres",
+    feature = "sinks-sqlite"
+))]
+pub mod database;
 #[cfg(feature = "sinks-databend")]
 pub mod databend;
 #[cfg(any(
@@ -80,6 +86,8 @@ pub mod keep;
 pub mod loki;
 #[cfg(feature = "sinks-mezmo")]
 pub mod mezmo;
+#[cfg(feature = "sinks-mongodb")]
+pub mod mongodb;
 #[cfg(feature = "sinks-mqtt")]
 pub mod mqtt;
 #[cfg(feature = "sinks-nats")]
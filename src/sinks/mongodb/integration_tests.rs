@@ -0,0 +1,170 @@
+use futures::{TryStreamExt, stream};
+use mongodb::{
+    Client,
+    bson::{Document, doc},
+};
+use tower::Service;
+use vector_lib::event::{BatchNotifier, BatchStatus, Event, LogEvent};
+
+use super::{
+    MongoDbConfig,
+    service::{MongoDbRequest, MongoDbService},
+};
+use crate::{
+    config::{SinkConfig, SinkContext},
+    test_util::{
+        components::{
+            COMPONENT_ERROR_TAGS, run_and_assert_sink_compliance, run_and_assert_sink_error,
+        },
+        random_string, trace_init,
+    },
+};
+
+const MONGODB_SINK_TAGS: [&str; 2] = ["endpoint", "protocol"];
+
+fn mongodb_address() -> String {
+    std::env::var("PRIMARY_MONGODB_ADDRESS").unwrap_or_else(|_| "mongodb://localhost:27017".into())
+}
+
+fn config(database: &str, extra: &str) -> MongoDbConfig {
+    toml::from_str(&format!(
+        r#"
+            endpoint = "{}"
+            database = "{database}"
+            collection = "logs-{{{{ application }}}}"
+            {extra}
+        "#,
+        mongodb_address()
+    ))
+    .unwrap()
+}
+
+fn create_event(id: i64, application: &str) -> Event {
+    let mut event = LogEvent::from("raw log line");
+    event.insert("id", id);
+    event.insert("application", application);
+    event.into()
+}
+
+async fn find_ids(database: &str, collection: &str) -> Vec<i64> {
+    let client = Client::with_uri_str(mongodb_address()).await.unwrap();
+    client
+        .database(database)
+        .collection::<Document>(collection)
+        .find(doc! {})
+        .sort(doc! { "id": 1 })
+        .await
+        .unwrap()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|document| document.get_i64("id").unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn healthcheck_passes() {
+    trace_init();
+
+    let (_sink, healthcheck) = config("vector", "")
+        .build(SinkContext::default())
+        .await
+        .unwrap();
+    healthcheck.await.unwrap();
+}
+
+#[tokio::test]
+async fn healthcheck_fails_unknown_host() {
+    trace_init();
+
+    let config = toml::from_str::<MongoDbConfig>(
+        r#"
+            endpoint = "mongodb://unknown-host:27017/?serverSelectionTimeoutMS=1000"
+            database = "vector"
+            collection = "logs"
+        "#,
+    )
+    .unwrap();
+    let (_sink, healthcheck) = config.build(SinkContext::default()).await.unwrap();
+    assert!(healthcheck.await.is_err());
+}
+
+#[tokio::test]
+async fn insert_into_templated_collections() {
+    trace_init();
+
+    let database = format!("vector_{}", random_string(10));
+    let (sink, _healthcheck) = config(&database, r#"write_concern.w = "majority""#)
+        .build(SinkContext::default())
+        .await
+        .unwrap();
+
+    let mut events = (0..10)
+        .map(|id| create_event(id, if id % 2 == 0 { "even" } else { "odd" }))
+        .collect::<Vec<_>>();
+    let mut receiver = BatchNotifier::apply_to(&mut events);
+    run_and_assert_sink_compliance(sink, stream::iter(events), &MONGODB_SINK_TAGS).await;
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    assert_eq!(find_ids(&database, "logs-even").await, vec![0, 2, 4, 6, 8]);
+    assert_eq!(find_ids(&database, "logs-odd").await, vec![1, 3, 5, 7, 9]);
+}
+
+#[tokio::test]
+async fn insertion_fails_unsatisfiable_write_concern() {
+    trace_init();
+
+    let database = format!("vector_{}", random_string(10));
+    // The test deployment only has two data-bearing nodes.
+    let (sink, _healthcheck) = config(&database, "write_concern.w = 3\nrequest.retry_attempts = 0")
+        .build(SinkContext::default())
+        .await
+        .unwrap();
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let event = create_event(0, "app").with_batch_notifier(&batch);
+    drop(batch);
+    run_and_assert_sink_error(sink, stream::iter(vec![event]), &COMPONENT_ERROR_TAGS).await;
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Rejected));
+}
+
+#[tokio::test]
+async fn skips_documents_inserted_by_an_earlier_attempt() {
+    trace_init();
+
+    let database = format!("vector_{}", random_string(10));
+    let client = Client::with_uri_str(mongodb_address()).await.unwrap();
+    let mut service = MongoDbService::new(client, database.clone(), mongodb_address());
+
+    let events = (0..2).map(|id| create_event(id, "app")).collect();
+    let request = MongoDbRequest::new("logs-app".to_owned(), events).unwrap();
+    service.call(request.clone()).await.unwrap();
+    service.call(request).await.unwrap();
+
+    assert_eq!(find_ids(&database, "logs-app").await, vec![0, 1]);
+}
+
+#[tokio::test]
+async fn rejects_duplicates_of_event_ids() {
+    trace_init();
+
+    let database = format!("vector_{}", random_string(10));
+    let client = Client::with_uri_str(mongodb_address()).await.unwrap();
+    client
+        .database(&database)
+        .collection::<Document>("logs-app")
+        .insert_one(doc! { "_id": "first", "id": 0_i64 })
+        .await
+        .unwrap();
+    let (sink, _healthcheck) = config(&database, "")
+        .build(SinkContext::default())
+        .await
+        .unwrap();
+
+    let mut events = (0..2).map(|id| create_event(id, "app")).collect::<Vec<_>>();
+    events[0].as_mut_log().insert("_id", "first");
+    let mut receiver = BatchNotifier::apply_to(&mut events);
+    run_and_assert_sink_error(sink, stream::iter(events), &COMPONENT_ERROR_TAGS).await;
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Rejected));
+}
@@ -0,0 +1,13 @@
+//! The MongoDB [`vector_lib::sink::VectorSink`].
+//!
+//! Events are inserted as BSON documents into a collection, one `insertMany` command per batch.
+//! Batches are partitioned by the rendered collection name, so a single sink can write to
+//! several collections of the same database.
+
+mod config;
+#[cfg(all(test, feature = "mongodb_sink-integration-tests"))]
+mod integration_tests;
+mod service;
+mod sink;
+
+pub use self::config::MongoDbConfig;
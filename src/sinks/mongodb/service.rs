@@ -0,0 +1,298 @@
+use std::{
+    collections::HashSet,
+    num::NonZeroUsize,
+    task::{Context, Poll},
+};
+
+use futures::future::BoxFuture;
+use mongodb::{
+    Client,
+    bson::{Bson, DateTime, Document, oid::ObjectId},
+    error::{Error as MongoError, ErrorKind, InsertManyError, RETRYABLE_WRITE_ERROR},
+};
+use snafu::{ResultExt, Snafu};
+use tower::Service;
+use vector_lib::{
+    EstimatedJsonEncodedSizeOf,
+    event::{Event, EventFinalizers, EventStatus, Finalizable, ObjectMap, Value},
+    request_metadata::{GroupedCountByteSize, MetaDescriptive, RequestMetadata},
+    stream::DriverResponse,
+};
+
+use crate::{
+    internal_events::EndpointBytesSent,
+    sinks::prelude::{RequestMetadataBuilder, RetryLogic},
+};
+
+const MONGODB_PROTOCOL: &str = "mongodb";
+
+/// The error code of a write that conflicts with a document with the same unique key.
+const DUPLICATE_KEY_ERROR: i32 = 11000;
+
+/// How the server names the `_id` index in duplicate key errors.
+const ID_INDEX: &str = " index: _id_ ";
+
+#[derive(Clone)]
+pub struct MongoDbRetryLogic;
+
+impl RetryLogic for MongoDbRetryLogic {
+    type Error = MongoDbServiceError;
+    type Request = MongoDbRequest;
+    type Response = MongoDbResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        let MongoDbServiceError::MongoDb { source } = error;
+        source.contains_label(RETRYABLE_WRITE_ERROR)
+            || matches!(
+                *source.kind,
+                ErrorKind::Io(_) | ErrorKind::ServerSelection { .. }
+            )
+    }
+}
+
+#[derive(Clone)]
+pub struct MongoDbService {
+    client: Client,
+    database: String,
+    endpoint: String,
+}
+
+impl MongoDbService {
+    pub const fn new(client: Client, database: String, endpoint: String) -> Self {
+        Self {
+            client,
+            database,
+            endpoint,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MongoDbRequest {
+    pub collection: String,
+    /// The documents of the events, each with an `_id` so that retries insert the same documents.
+    pub documents: Vec<Document>,
+    /// The indexes of the documents whose `_id` was assigned here rather than by the event.
+    pub generated_ids: HashSet<usize>,
+    pub finalizers: EventFinalizers,
+    pub metadata: RequestMetadata,
+}
+
+impl MongoDbRequest {
+    pub fn new(collection: String, mut events: Vec<Event>) -> Result<Self, String> {
+        let finalizers = events.take_finalizers();
+        let metadata_builder = RequestMetadataBuilder::from_events(&events);
+        let events_size = NonZeroUsize::new(events.estimated_json_encoded_size_of().get())
+            .ok_or("payload should never be zero length")?;
+        let metadata = metadata_builder.with_request_size(events_size);
+        let mut generated_ids = HashSet::new();
+        let documents = events
+            .iter()
+            .filter_map(Event::maybe_as_log)
+            .filter_map(|log| log.as_map().map(to_document))
+            .enumerate()
+            .map(|(index, mut document)| {
+                if !document.contains_key("_id") {
+                    document.insert("_id", ObjectId::new());
+                    generated_ids.insert(index);
+                }
+                document
+            })
+            .collect();
+        Ok(MongoDbRequest {
+            collection,
+            documents,
+            generated_ids,
+            finalizers,
+            metadata,
+        })
+    }
+}
+
+impl Finalizable for MongoDbRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        self.finalizers.take_finalizers()
+    }
+}
+
+impl MetaDescriptive for MongoDbRequest {
+    fn get_metadata(&self) -> &RequestMetadata {
+        &self.metadata
+    }
+
+    fn metadata_mut(&mut self) -> &mut RequestMetadata {
+        &mut self.metadata
+    }
+}
+
+pub struct MongoDbResponse {
+    metadata: RequestMetadata,
+}
+
+impl DriverResponse for MongoDbResponse {
+    fn event_status(&self) -> EventStatus {
+        EventStatus::Delivered
+    }
+
+    fn events_sent(&self) -> &GroupedCountByteSize {
+        self.metadata.events_estimated_json_encoded_byte_size()
+    }
+
+    fn bytes_sent(&self) -> Option<usize> {
+        Some(self.metadata.request_encoded_size())
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum MongoDbServiceError {
+    #[snafu(display("MongoDB error: {source}"))]
+    MongoDb { source: MongoError },
+}
+
+fn to_bson(value: &Value) -> Bson {
+    match value {
+        Value::Bytes(bytes) => Bson::String(String::from_utf8_lossy(bytes).into_owned()),
+        Value::Regex(regex) => Bson::String(regex.as_str().to_owned()),
+        Value::Integer(integer) => Bson::Int64(*integer),
+        Value::Float(float) => Bson::Double(float.into_inner()),
+        Value::Boolean(boolean) => Bson::Boolean(*boolean),
+        // BSON dates only have millisecond precision.
+        Value::Timestamp(timestamp) => {
+            Bson::DateTime(DateTime::from_millis(timestamp.timestamp_millis()))
+        }
+        Value::Object(object) => Bson::Document(to_document(object)),
+        Value::Array(array) => Bson::Array(array.iter().map(to_bson).collect()),
+        Value::Null => Bson::Null,
+    }
+}
+
+/// Whether `error` only reports documents that an earlier attempt at the request inserted, as
+/// when a batch that was partially inserted is retried.
+///
+/// Those are the documents whose generated `_id` is already in the collection. A duplicate of an
+/// `_id` the event carried, or of another unique key, conflicts with a document the sink did not
+/// necessarily write, so it is not skipped.
+fn only_retried_duplicates(error: &MongoError, generated_ids: &HashSet<usize>) -> bool {
+    match &*error.kind {
+        ErrorKind::InsertMany(InsertManyError {
+            write_errors: Some(write_errors),
+            write_concern_error: None,
+            ..
+        }) => write_errors.iter().all(|error| {
+            error.code == DUPLICATE_KEY_ERROR
+                && error.message.contains(ID_INDEX)
+                && generated_ids.contains(&error.index)
+        }),
+        _ => false,
+    }
+}
+
+fn to_document(object: &ObjectMap) -> Document {
+    object
+        .iter()
+        .map(|(key, value)| (key.to_string(), to_bson(value)))
+        .collect()
+}
+
+impl Service<MongoDbRequest> for MongoDbService {
+    type Response = MongoDbResponse;
+    type Error = MongoDbServiceError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: MongoDbRequest) -> Self::Future {
+        let service = self.clone();
+        let future = async move {
+            let metadata = request.metadata;
+            // Unordered, so that a retry of a partially inserted batch still inserts the documents
+            // after the ones that were inserted.
+            let result = service
+                .client
+                .database(&service.database)
+                .collection::<Document>(&request.collection)
+                .insert_many(request.documents)
+                .ordered(false)
+                .await;
+            match result {
+                Ok(_) => {}
+                Err(error) if only_retried_duplicates(&error, &request.generated_ids) => {
+                    debug!(message = "Skipped documents that were already inserted.");
+                }
+                Err(error) => return Err(error).context(MongoDbSnafu),
+            }
+
+            emit!(EndpointBytesSent {
+                byte_size: metadata.request_encoded_size(),
+                protocol: MONGODB_PROTOCOL,
+                endpoint: &service.endpoint,
+            });
+
+            Ok(MongoDbResponse { metadata })
+        };
+
+        Box::pin(future)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use mongodb::bson::doc;
+    use vector_lib::event::LogEvent;
+
+    use super::*;
+
+    #[test]
+    fn converts_values_to_bson() {
+        let timestamp = Utc.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap();
+        let value = Value::from(btreemap! {
+            "message" => "hello",
+            "count" => 3,
+            "ratio" => 0.5,
+            "ok" => true,
+            "timestamp" => timestamp,
+            "tags" => vec!["a", "b"],
+            "nested" => btreemap! { "missing" => Value::Null },
+        });
+
+        assert_eq!(
+            to_document(value.as_object().unwrap()),
+            doc! {
+                "count": 3_i64,
+                "message": "hello",
+                "nested": { "missing": Bson::Null },
+                "ok": true,
+                "ratio": 0.5,
+                "tags": ["a", "b"],
+                "timestamp": DateTime::from_millis(timestamp.timestamp_millis()),
+            }
+        );
+    }
+
+    #[test]
+    fn assigns_document_ids_once() {
+        let mut with_id = LogEvent::from("kept");
+        with_id.insert("_id", "existing");
+        let events = vec![
+            Event::from(LogEvent::from("assigned")),
+            Event::from(with_id),
+        ];
+        let request = MongoDbRequest::new("logs".to_owned(), events).unwrap();
+
+        let ids = |request: &MongoDbRequest| {
+            request
+                .documents
+                .iter()
+                .map(|document| document.get("_id").cloned())
+                .collect::<Vec<_>>()
+        };
+        let retried = request.clone();
+        assert_eq!(ids(&request), ids(&retried));
+        assert!(matches!(ids(&request)[0], Some(Bson::ObjectId(_))));
+        assert_eq!(ids(&request)[1], Some(Bson::String("existing".to_owned())));
+        assert_eq!(request.generated_ids, HashSet::from([0]));
+    }
+}
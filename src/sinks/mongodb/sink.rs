@@ -0,0 +1,76 @@
+use super::service::{MongoDbRequest, MongoDbRetryLogic, MongoDbService};
+use crate::sinks::prelude::*;
+
+pub struct MongoDbSink {
+    service: Svc<MongoDbService, MongoDbRetryLogic>,
+    collection: Template,
+    batch_settings: BatcherSettings,
+}
+
+impl MongoDbSink {
+    pub const fn new(
+        service: Svc<MongoDbService, MongoDbRetryLogic>,
+        collection: Template,
+        batch_settings: BatcherSettings,
+    ) -> Self {
+        Self {
+            service,
+            collection,
+            batch_settings,
+        }
+    }
+
+    async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let batch_settings = self.batch_settings;
+
+        input
+            .batched_partitioned(
+                CollectionPartitioner(self.collection),
+                batch_settings.timeout,
+                |_| batch_settings.as_byte_size_config(),
+            )
+            .filter_map(|(collection, events)| async move {
+                match MongoDbRequest::new(collection?, events) {
+                    Ok(request) => Some(request),
+                    Err(e) => {
+                        warn!(
+                            message = "Error creating mongodb sink's request.",
+                            error = %e
+                        );
+                        None
+                    }
+                }
+            })
+            .into_driver(self.service)
+            .run()
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl StreamSink<Event> for MongoDbSink {
+    async fn run(mut self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.run_inner(input).await
+    }
+}
+
+/// Partitions events by their rendered collection name.
+struct CollectionPartitioner(Template);
+
+impl Partitioner for CollectionPartitioner {
+    type Item = Event;
+    type Key = Option<String>;
+
+    fn partition(&self, item: &Self::Item) -> Self::Key {
+        self.0
+            .render_string(item)
+            .map_err(|error| {
+                emit!(TemplateRenderingError {
+                    error,
+                    field: Some("collection"),
+                    drop_event: true,
+                });
+            })
+            .ok()
+    }
+}
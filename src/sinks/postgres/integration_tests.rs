@@ -14,7 +14,11 @@ use vrl::event_path;
 use crate::{
     config::{SinkConfig, SinkContext},
     event::{ObjectMap, TraceEvent, Value},
-    sinks::{postgres::PostgresConfig, util::test::load_sink},
+    sinks::{
+        database::ConflictPolicy,
+        postgres::{BulkMode, PostgresConfig},
+        util::test::load_sink,
+    },
     test_util::{
         addr::next_addr,
         components::{
@@ -170,7 +174,11 @@ async fn prepare_config() -> (PostgresConfig, String, PgConnection) {
 #[tokio::test]
 async fn healthcheck_passes() {
     trace_init();
-    let (config, _table, _connection) = prepare_config().await;
+    let (config, table, mut connection) = prepare_config().await;
+    sqlx::query(&format!("CREATE TABLE {table} (id BIGINT, message TEXT)"))
+        .execute(&mut connection)
+        .await
+        .unwrap();
     let (_sink, healthcheck) = config
         .build(SinkContext::default())
         .await
@@ -178,6 +186,18 @@ async fn healthcheck_passes() {
     assert!(healthcheck.await.is_ok());
 }
 
+#[tokio::test]
+async fn healthcheck_fails_missing_table() {
+    trace_init();
+    let (config, _table, _connection) = prepare_config().await;
+    let (_sink, healthcheck) = config
+        .build(SinkContext::default())
+        .await
+        .expect("sink should build successfully");
+    let error = healthcheck.await.unwrap_err();
+    assert!(error.to_string().contains("does not exist"));
+}
+
 #[tokio::test]
 async fn healthcheck_fails_unknown_host() {
     trace_init();
@@ -295,6 +315,77 @@ async fn insert_multiple_events() {
     assert_eq!(expected_values, actual_values);
 }
 
+#[tokio::test]
+async fn insert_into_json_column() {
+    trace_init();
+
+    let (mut config, table, mut connection) = prepare_config().await;
+    config.json_column = Some("event".to_string());
+    let (sink, _hc) = config.build(SinkContext::default()).await.unwrap();
+    let create_table_sql = format!("CREATE TABLE {table} (id BIGSERIAL, event JSONB)");
+    sqlx::query(&create_table_sql)
+        .execute(&mut connection)
+        .await
+        .unwrap();
+
+    let metric = create_metric("counter");
+    let expected_value = serde_json::to_value(&metric).unwrap();
+    let input_event = Event::from(metric);
+
+    run_and_assert_sink_compliance(sink, stream::once(ready(input_event)), &POSTGRES_SINK_TAGS)
+        .await;
+
+    let select_all_sql = format!("SELECT event FROM {table}");
+    let actual_value: (serde_json::Value,) = sqlx::query_as(&select_all_sql)
+        .fetch_one(&mut connection)
+        .await
+        .unwrap();
+    assert_eq!(expected_value, actual_value.0);
+}
+
+#[tokio::test]
+async fn copy_multiple_events() {
+    trace_init();
+
+    let (mut config, table, mut connection) = prepare_config().await;
+    config.bulk = BulkMode::Copy;
+    config.batch.max_events = Some(100);
+    let (sink, _hc) = config.build(SinkContext::default()).await.unwrap();
+    let create_table_sql = format!(
+        "CREATE TABLE {table} (id BIGINT, host TEXT, timestamp TIMESTAMPTZ, message TEXT, payload JSONB)"
+    );
+    sqlx::query(&create_table_sql)
+        .execute(&mut connection)
+        .await
+        .unwrap();
+
+    let (input_events, mut receiver) = create_events(100);
+    let input_log_events = input_events
+        .clone()
+        .into_iter()
+        .map(Event::into_log)
+        .collect::<Vec<_>>();
+    let expected_values = input_log_events
+        .iter()
+        .map(|event| serde_json::to_value(event).unwrap())
+        .collect::<Vec<_>>();
+    run_and_assert_sink_compliance(sink, stream::iter(input_events), &POSTGRES_SINK_TAGS).await;
+    // We drop the event to notify the receiver that the batch was delivered.
+    std::mem::drop(input_log_events);
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    let select_all_sql = format!("SELECT * FROM {table} ORDER BY id");
+    let actual_events: Vec<TestEvent> = sqlx::query_as(&select_all_sql)
+        .fetch_all(&mut connection)
+        .await
+        .unwrap();
+    let actual_values = actual_events
+        .iter()
+        .map(|event| serde_json::to_value(event).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(expected_values, actual_values);
+}
+
 #[tokio::test]
 async fn insert_metric() {
     trace_init();
@@ -514,3 +605,33 @@ async fn insertion_fails_primary_key_violation() {
     )
     .await;
 }
+
+#[tokio::test]
+async fn upsert_updates_existing_rows() {
+    trace_init();
+
+    let (mut config, table, mut connection) = prepare_config().await;
+    config.conflict = ConflictPolicy::Upsert;
+    config.key_columns = vec!["id".to_owned()];
+    let (sink, _hc) = config.build(SinkContext::default()).await.unwrap();
+    let create_table_sql = format!(
+        "CREATE TABLE {table} (id BIGINT PRIMARY KEY, host TEXT, timestamp TIMESTAMPTZ, message TEXT, payload JSONB)"
+    );
+    sqlx::query(&create_table_sql)
+        .execute(&mut connection)
+        .await
+        .unwrap();
+
+    let first = create_event(0);
+    let mut second = create_event(0);
+    second.as_mut_log().insert("message", "updated log line");
+    run_and_assert_sink_compliance(sink, stream::iter(vec![first, second]), &POSTGRES_SINK_TAGS)
+        .await;
+
+    let select_all_sql = format!("SELECT id, message FROM {table}");
+    let rows: Vec<(i64, String)> = sqlx::query_as(&select_all_sql)
+        .fetch_all(&mut connection)
+        .await
+        .unwrap();
+    assert_eq!(rows, [(0, "updated log line".to_owned())]);
+}
@@ -4,4 +4,4 @@ mod integration_tests;
 mod service;
 mod sink;
 
-pub use self::config::PostgresConfig;
+pub use self::config::{BulkMode, PostgresConfig};
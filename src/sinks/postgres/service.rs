@@ -1,11 +1,13 @@
 use std::{
     num::NonZeroUsize,
+    sync::Arc,
     task::{Context, Poll},
 };
 
 use futures::future::BoxFuture;
 use snafu::{ResultExt, Snafu};
-use sqlx::{Pool, Postgres, types::Json};
+use sqlx::{Pool, Postgres, postgres::PgPoolCopyExt, types::Json};
+use tokio::sync::OnceCell;
 use tower::Service;
 use vector_lib::{
     EstimatedJsonEncodedSizeOf,
@@ -15,9 +17,13 @@ use vector_lib::{
     stream::DriverResponse,
 };
 
+use super::config::BulkMode;
 use crate::{
     internal_events::EndpointBytesSent,
-    sinks::prelude::{RequestMetadataBuilder, RetryLogic},
+    sinks::{
+        database::{Dialect, OnConflict, is_retriable},
+        prelude::{RequestMetadataBuilder, RetryLogic},
+    },
 };
 
 const POSTGRES_PROTOCOL: &str = "postgres";
@@ -38,10 +44,7 @@ impl RetryLogic for PostgresRetryLogic {
             return false;
         };
 
-        matches!(
-            postgres_error,
-            sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut
-        )
+        is_retriable(Dialect::Postgres, postgres_error)
     }
 }
 
@@ -49,17 +52,147 @@ impl RetryLogic for PostgresRetryLogic {
 pub struct PostgresService {
     connection_pool: Pool<Postgres>,
     table: String,
+    json_column: Option<String>,
+    bulk: BulkMode,
+    on_conflict: OnConflict,
+    /// The columns of the table, looked up before the first batch is copied into it, or before
+    /// the first batch is inserted with an upsert.
+    columns: Arc<OnceCell<Vec<String>>>,
+    /// The statement that batches are inserted with, rendered before the first batch.
+    insert_statement: Arc<OnceCell<String>>,
     endpoint: String,
 }
 
 impl PostgresService {
-    pub const fn new(connection_pool: Pool<Postgres>, table: String, endpoint: String) -> Self {
+    pub fn new(
+        connection_pool: Pool<Postgres>,
+        table: String,
+        json_column: Option<String>,
+        bulk: BulkMode,
+        on_conflict: OnConflict,
+        endpoint: String,
+    ) -> Self {
         Self {
             connection_pool,
             table,
+            json_column,
+            bulk,
+            on_conflict,
+            columns: Arc::default(),
+            insert_statement: Arc::default(),
             endpoint,
         }
     }
+
+    async fn insert_statement(&self) -> Result<&String, sqlx::Error> {
+        self.insert_statement
+            .get_or_try_init(|| async {
+                let table = &self.table;
+                let statement = match &self.json_column {
+                    Some(column) => format!(
+                        "INSERT INTO {table} ({}) SELECT value FROM jsonb_array_elements($1)",
+                        Dialect::Postgres.quote_identifier(column)
+                    ),
+                    None => format!(
+                        "INSERT INTO {table} SELECT * FROM \
+                         jsonb_populate_recordset(NULL::{table}, $1)"
+                    ),
+                };
+                // The columns that an upsert updates are only looked up if there is a conflict
+                // clause.
+                if self.on_conflict.clause(Dialect::Postgres, &[]).is_none() {
+                    return Ok(statement);
+                }
+                let columns = match &self.json_column {
+                    Some(column) => vec![column.clone()],
+                    None => self.columns().await?.clone(),
+                };
+                let clause = self
+                    .on_conflict
+                    .clause(Dialect::Postgres, &columns)
+                    .unwrap_or_default();
+                Ok(statement + &clause)
+            })
+            .await
+    }
+
+    async fn insert(&self, values: Vec<serde_json::Value>) -> Result<(), sqlx::Error> {
+        sqlx::query(self.insert_statement().await?)
+            .bind(Json(values))
+            .execute(&self.connection_pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Streams `values` into the table with `COPY`, as one CSV record per event.
+    async fn copy(&self, values: Vec<serde_json::Value>) -> Result<(), sqlx::Error> {
+        let columns = match &self.json_column {
+            Some(column) => vec![column.clone()],
+            None => self.columns().await?.clone(),
+        };
+        let mut data = Vec::new();
+        for value in &values {
+            match &self.json_column {
+                Some(_) => write_csv_record(&mut data, [Some(value)]),
+                None => write_csv_record(
+                    &mut data,
+                    columns.iter().map(|column| value.get(column.as_str())),
+                ),
+            }
+        }
+
+        let columns = columns
+            .iter()
+            .map(|column| format!("\"{}\"", column.replace('"', "\"\"")))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let statement = format!(
+            "COPY {} ({columns}) FROM STDIN WITH (FORMAT csv)",
+            self.table
+        );
+        let mut copy = self.connection_pool.copy_in_raw(&statement).await?;
+        copy.send(data).await?;
+        copy.finish().await?;
+        Ok(())
+    }
+
+    async fn columns(&self) -> Result<&Vec<String>, sqlx::Error> {
+        self.columns
+            .get_or_try_init(|| {
+                sqlx::query_scalar(
+                    "SELECT attname::text FROM pg_attribute \
+                     WHERE attrelid = $1::text::regclass AND attnum > 0 AND NOT attisdropped \
+                     ORDER BY attnum",
+                )
+                .bind(&self.table)
+                .fetch_all(&self.connection_pool)
+            })
+            .await
+    }
+}
+
+/// Writes a CSV record of `values`, where every value is quoted so that a missing or `null`
+/// value, which is written as an unquoted empty field, is copied as `NULL`.
+///
+/// Strings are written as they are, and all other values as JSON.
+fn write_csv_record<'a>(
+    data: &mut Vec<u8>,
+    values: impl IntoIterator<Item = Option<&'a serde_json::Value>>,
+) {
+    for (index, value) in values.into_iter().enumerate() {
+        if index > 0 {
+            data.push(b',');
+        }
+        let text = match value {
+            None | Some(serde_json::Value::Null) => continue,
+            Some(serde_json::Value::String(text)) => text.clone(),
+            Some(value) => value.to_string(),
+        };
+        data.push(b'"');
+        data.extend_from_slice(text.replace('"', "\"\"").as_bytes());
+        data.push(b'"');
+    }
+    data.push(b'\n');
 }
 
 #[derive(Clone)]
@@ -141,7 +274,6 @@ impl Service<PostgresRequest> for PostgresService {
     fn call(&mut self, request: PostgresRequest) -> Self::Future {
         let service = self.clone();
         let future = async move {
-            let table = service.table;
             let metadata = request.metadata;
             let json_serializer = JsonSerializerConfig::default().build();
             let serialized_values = request
@@ -151,12 +283,10 @@ impl Service<PostgresRequest> for PostgresService {
                 .collect::<Result<Vec<_>, _>>()
                 .context(VectorCommonSnafu)?;
 
-            sqlx::query(&format!(
-                "INSERT INTO {table} SELECT * FROM jsonb_populate_recordset(NULL::{table}, $1)"
-            ))
-            .bind(Json(serialized_values))
-            .execute(&service.connection_pool)
-            .await
+            match service.bulk {
+                BulkMode::Insert => service.insert(serialized_values).await,
+                BulkMode::Copy => service.copy(serialized_values).await,
+            }
             .context(PostgresSnafu)?;
 
             emit!(EndpointBytesSent {
@@ -171,3 +301,31 @@ impl Service<PostgresRequest> for PostgresService {
         Box::pin(future)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn writes_csv_records() {
+        let event = json!({
+            "message": "say \"hi\"",
+            "empty": "",
+            "status": 200,
+            "tags": { "env": "prod" },
+            "missing": null,
+        });
+        let mut data = Vec::new();
+        write_csv_record(
+            &mut data,
+            ["message", "empty", "status", "tags", "missing", "absent"]
+                .map(|field| event.get(field)),
+        );
+        assert_eq!(
+            String::from_utf8(data).unwrap(),
+            "\"say \"\"hi\"\"\",\"\",\"200\",\"{\"\"env\"\":\"\"prod\"\"}\",,\n"
+        );
+    }
+}
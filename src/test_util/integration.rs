@@ -26,3 +26,15 @@ pub mod postgres {
             .unwrap_or_else(|_| format!("postgres://vector:vector@{}/postgres", pg_host()))
     }
 }
+
+#[cfg(feature = "mysql-integration-tests")]
+pub mod mysql {
+    pub fn mysql_host() -> String {
+        std::env::var("MYSQL_HOST").unwrap_or_else(|_| "localhost".into())
+    }
+
+    pub fn mysql_url() -> String {
+        std::env::var("MYSQL_URL")
+            .unwrap_or_else(|_| format!("mysql://vector:vector@{}:3306/vector", mysql_host()))
+    }
+}
@@ -583,6 +583,8 @@ impl<'a> Builder<'a> {
                         match sink.buffer.stages().first().expect("cant ever be empty") {
                             BufferType::Memory { .. } => "memory",
                             BufferType::DiskV2 { .. } => "disk",
+                            #[cfg(feature = "buffers-sqlite")]
+                            BufferType::Sqlite { .. } => "sqlite",
                         };
                     let buffer_span = error_span!("sink", buffer_type);
                     let buffer = sink
@@ -0,0 +1,147 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use vector_lib::configurable::configurable_component;
+
+use super::transform::Exec;
+use crate::{
+    config::{
+        DataType, GenerateConfig, Input, OutputId, TransformConfig, TransformContext,
+        TransformOutput,
+    },
+    schema::{self, Definition},
+    transforms::Transform,
+};
+
+/// Configuration for the `exec` transform.
+#[configurable_component(transform(
+    "exec",
+    "Pass events through a long-running external process over its standard input and output."
+))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ExecConfig {
+    /// The command to run, plus any arguments required.
+    ///
+    /// The process reads events from its standard input and writes the resulting events to its
+    /// standard output, each encoded as a JSON object and framed according to `protocol`. It may
+    /// write any number of events for each event that it reads, and its standard error is
+    /// inherited from Vector.
+    ///
+    /// Each event that the process writes carries the metadata of the last event written to it.
+    /// The events written to the process are acknowledged together with the next event it writes,
+    /// so events that it filters out are only acknowledged once it writes another event.
+    #[configurable(metadata(docs::examples = "python3", docs::examples = "transform.py"))]
+    pub command: Vec<String>,
+
+    /// Custom environment variables to set or update when running the command.
+    /// If a variable name already exists in the environment, its value is replaced.
+    #[serde(default)]
+    #[configurable(metadata(docs::additional_props_description = "An environment variable."))]
+    #[configurable(metadata(docs::examples = "environment_examples()"))]
+    pub environment: Option<HashMap<String, String>>,
+
+    /// Whether or not to clear the environment before setting custom environment variables.
+    #[serde(default)]
+    pub clear_environment: bool,
+
+    /// The directory in which to run the command.
+    pub working_directory: Option<PathBuf>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub protocol: ExecProtocol,
+
+    /// The amount of time, in seconds, to wait before respawning the process after it exits.
+    ///
+    /// The wait doubles each time that the process exits without writing an event, up to
+    /// `max_respawn_interval_secs`. Events that the process has read but not yet answered when it
+    /// exits are lost, and reported to their sources as failed.
+    #[serde(default = "default_respawn_interval_secs")]
+    #[configurable(metadata(docs::human_name = "Respawn Interval"))]
+    pub respawn_interval_secs: u64,
+
+    /// The maximum amount of time, in seconds, to wait before respawning the process.
+    #[serde(default = "default_max_respawn_interval_secs")]
+    #[configurable(metadata(docs::human_name = "Maximum Respawn Interval"))]
+    pub max_respawn_interval_secs: u64,
+}
+
+/// How events are framed on the standard input and output of the process.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecProtocol {
+    /// Each event is a line of JSON.
+    #[default]
+    Ndjson,
+
+    /// Each event is a JSON payload, preceded by its length in bytes as a 4-byte big-endian
+    /// integer.
+    LengthPrefixed,
+}
+
+const fn default_respawn_interval_secs() -> u64 {
+    1
+}
+
+const fn default_max_respawn_interval_secs() -> u64 {
+    60
+}
+
+fn environment_examples() -> HashMap<String, String> {
+    HashMap::<_, _>::from_iter([
+        ("LANG".to_owned(), "es_ES.UTF-8".to_owned()),
+        ("TZ".to_owned(), "Etc/UTC".to_owned()),
+        ("PATH".to_owned(), "/bin:/usr/bin:/usr/local/bin".to_owned()),
+    ])
+}
+
+impl GenerateConfig for ExecConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"command = ["cat"]"#).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "exec")]
+impl TransformConfig for ExecConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        Exec::new(self).map(Transform::event_task)
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn outputs(
+        &self,
+        _: &TransformContext,
+        input_definitions: &[(OutputId, schema::Definition)],
+    ) -> Vec<TransformOutput> {
+        // The events are rebuilt from the output of the process, so the type definition is reset
+        let namespaces = input_definitions
+            .iter()
+            .flat_map(|(_output, definition)| definition.log_namespaces().clone())
+            .collect();
+
+        let definition = input_definitions
+            .iter()
+            .map(|(output, _definition)| {
+                (
+                    output.clone(),
+                    Definition::default_for_namespace(&namespaces),
+                )
+            })
+            .collect();
+
+        vec![TransformOutput::new(DataType::Log, definition)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<super::ExecConfig>();
+    }
+}
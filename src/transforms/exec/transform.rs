@@ -0,0 +1,381 @@
+use std::{pin::Pin, process::Stdio, time::Duration};
+
+use async_stream::stream;
+use bytes::{BufMut, Bytes, BytesMut};
+use futures::{Stream, StreamExt, stream::BoxStream};
+use tokio::{
+    io::AsyncWriteExt,
+    process::{Child, ChildStdin, ChildStdout, Command},
+    sync::mpsc,
+};
+use tokio_util::codec::{FramedRead, LengthDelimitedCodec, LinesCodec};
+use vector_lib::event::{EventFinalizers, EventMetadata, EventStatus, Finalizable, LogEvent};
+
+use super::config::{ExecConfig, ExecProtocol};
+use crate::{
+    common::backoff::ExponentialBackoff,
+    event::Event,
+    internal_events::{
+        ExecTransformEncodeError, ExecTransformExitedError, ExecTransformParseError,
+        ExecTransformReadError, ExecTransformSpawnError, ExecTransformWriteError,
+    },
+    transforms::TaskTransform,
+};
+
+/// The number of encoded events that are queued for the standard input of the process.
+const FRAME_QUEUE_SIZE: usize = 128;
+
+/// The maximum length of an event written by the process, which is also the default limit of
+/// length-prefixed frames.
+const MAX_FRAME_LENGTH: usize = 8 * 1024 * 1024;
+
+pub struct Exec {
+    config: ExecConfig,
+}
+
+/// A running process, with a task writing the frames sent to `frames` to its standard input.
+struct Process {
+    child: Child,
+    frames: mpsc::Sender<Bytes>,
+    output: BoxStream<'static, std::io::Result<Bytes>>,
+}
+
+impl Exec {
+    pub fn new(config: &ExecConfig) -> crate::Result<Self> {
+        if config.command.is_empty() {
+            return Err("`command` must contain at least the program to run.".into());
+        }
+        Ok(Self {
+            config: config.clone(),
+        })
+    }
+
+    fn command_line(&self) -> String {
+        self.config.command.join(" ")
+    }
+
+    fn build_command(&self) -> Command {
+        let mut command = Command::new(&self.config.command[0]);
+        command.args(&self.config.command[1..]);
+        command.kill_on_drop(true);
+
+        if self.config.clear_environment {
+            command.env_clear();
+        }
+        if let Some(envs) = &self.config.environment {
+            command.envs(envs);
+        }
+        if let Some(current_dir) = &self.config.working_directory {
+            command.current_dir(current_dir);
+        }
+
+        command.stdin(Stdio::piped());
+        command.stdout(Stdio::piped());
+        command.stderr(Stdio::inherit());
+        command
+    }
+
+    fn spawn(&self) -> std::io::Result<Process> {
+        let mut child = self.build_command().spawn()?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+
+        let (frames, receiver) = mpsc::channel(FRAME_QUEUE_SIZE);
+        tokio::spawn(write_frames(stdin, receiver, self.command_line()));
+
+        Ok(Process {
+            child,
+            frames,
+            output: read_frames(self.config.protocol, stdout),
+        })
+    }
+
+    fn backoff(&self) -> ExponentialBackoff {
+        // The delays are the powers of two, so they start at half the factor.
+        ExponentialBackoff::from_millis(2)
+            .factor(self.config.respawn_interval_secs.saturating_mul(500).max(1))
+            .max_delay(Duration::from_secs(self.config.max_respawn_interval_secs))
+    }
+}
+
+impl ExecProtocol {
+    fn encode(self, log: &LogEvent) -> serde_json::Result<Bytes> {
+        let payload = serde_json::to_vec(log)?;
+        let mut frame = BytesMut::with_capacity(payload.len() + 4);
+        match self {
+            Self::Ndjson => {
+                frame.put_slice(&payload);
+                frame.put_u8(b'\n');
+            }
+            Self::LengthPrefixed => {
+                frame.put_u32(payload.len() as u32);
+                frame.put_slice(&payload);
+            }
+        }
+        Ok(frame.freeze())
+    }
+}
+
+/// Decodes an event written by the process, with the metadata of the last event written to it.
+fn decode(frame: &[u8], metadata: &EventMetadata) -> crate::Result<LogEvent> {
+    let value = serde_json::from_slice::<serde_json::Value>(frame)?;
+    let (value, _) = LogEvent::try_from(value)?.into_parts();
+    Ok(LogEvent::from_parts(value, metadata.clone()))
+}
+
+fn read_frames(
+    protocol: ExecProtocol,
+    stdout: ChildStdout,
+) -> BoxStream<'static, std::io::Result<Bytes>> {
+    match protocol {
+        ExecProtocol::Ndjson => {
+            FramedRead::new(stdout, LinesCodec::new_with_max_length(MAX_FRAME_LENGTH))
+                .map(|line| line.map(Bytes::from).map_err(std::io::Error::other))
+                .boxed()
+        }
+        ExecProtocol::LengthPrefixed => FramedRead::new(
+            stdout,
+            LengthDelimitedCodec::builder()
+                .max_frame_length(MAX_FRAME_LENGTH)
+                .new_codec(),
+        )
+        .map(|frame| frame.map(BytesMut::freeze))
+        .boxed(),
+    }
+}
+
+/// Writes the frames to the standard input of the process, closing it once `frames` is closed.
+async fn write_frames(mut stdin: ChildStdin, mut frames: mpsc::Receiver<Bytes>, command: String) {
+    while let Some(frame) = frames.recv().await {
+        if let Err(error) = stdin.write_all(&frame).await {
+            emit!(ExecTransformWriteError {
+                command: &command,
+                error,
+            });
+            break;
+        }
+    }
+}
+
+impl TaskTransform<Event> for Exec {
+    fn transform(
+        self: Box<Self>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        Box::pin(stream! {
+            let command = self.command_line();
+            let mut backoff = self.backoff();
+            let mut output = Vec::new();
+            // The next frame to write, with the finalizers of its event, kept across respawns so
+            // that it is not lost.
+            let mut pending: Option<(Bytes, EventFinalizers)> = None;
+            // The finalizers of the events written to the process, which are attached to the next
+            // event that it writes, so that they are only acknowledged once its output is.
+            let mut in_flight = EventFinalizers::default();
+            // The metadata of the last event written to the process, given to the events it writes.
+            let mut metadata = EventMetadata::default();
+            let mut input_done = false;
+
+            loop {
+                let mut process = match self.spawn() {
+                    Ok(process) => process,
+                    Err(error) => {
+                        emit!(ExecTransformSpawnError { command: &command, error });
+                        if input_done {
+                            // No process is left to write the remaining events to.
+                            if let Some((_, finalizers)) = pending.take() {
+                                finalizers.update_status(EventStatus::Errored);
+                            }
+                            std::mem::take(&mut in_flight).update_status(EventStatus::Errored);
+                            break;
+                        }
+                        tokio::time::sleep(backoff.next().unwrap()).await;
+                        continue;
+                    }
+                };
+                let mut frames = Some(process.frames);
+
+                loop {
+                    tokio::select! {
+                        frame = process.output.next() => match frame {
+                            Some(Ok(frame)) => {
+                                backoff.reset();
+                                match decode(&frame, &metadata) {
+                                    Ok(mut log) => {
+                                        log.metadata_mut()
+                                            .merge_finalizers(std::mem::take(&mut in_flight));
+                                        output.push(Event::from(log));
+                                    }
+                                    Err(error) => emit!(ExecTransformParseError {
+                                        command: &command,
+                                        error,
+                                    }),
+                                }
+                            }
+                            Some(Err(error)) => {
+                                emit!(ExecTransformReadError { command: &command, error });
+                                break;
+                            }
+                            None => break,
+                        },
+                        permit = async { frames.as_ref().expect("frames is open").reserve().await },
+                            if pending.is_some() && frames.is_some() => match permit {
+                            Ok(permit) => {
+                                let (frame, finalizers) = pending.take().expect("frame is pending");
+                                permit.send(frame);
+                                in_flight.merge(finalizers);
+                                if input_done {
+                                    frames = None;
+                                }
+                            }
+                            // The process stopped reading, so wait for it to exit.
+                            Err(_) => frames = None,
+                        },
+                        event = input_rx.next(), if pending.is_none() && !input_done => {
+                            match event {
+                                Some(event) => {
+                                    let mut log = event.into_log();
+                                    let finalizers = log.take_finalizers();
+                                    match self.config.protocol.encode(&log) {
+                                        Ok(frame) => {
+                                            metadata = log.metadata().clone();
+                                            pending = Some((frame, finalizers));
+                                        }
+                                        Err(error) => emit!(ExecTransformEncodeError {
+                                            command: &command,
+                                            error,
+                                        }),
+                                    }
+                                }
+                                None => {
+                                    // Closing the standard input lets the process finish.
+                                    input_done = true;
+                                    frames = None;
+                                }
+                            }
+                        }
+                    }
+
+                    for event in output.drain(..) {
+                        yield event;
+                    }
+                }
+
+                drop(frames);
+                let exit_status = process.child.wait().await.ok().and_then(|status| status.code());
+                let finished = input_done && pending.is_none();
+                if finished && exit_status == Some(0) {
+                    break;
+                }
+                // The events that the process read without answering are lost.
+                std::mem::take(&mut in_flight).update_status(EventStatus::Errored);
+                emit!(ExecTransformExitedError { command: &command, exit_status });
+                if finished {
+                    break;
+                }
+                tokio::time::sleep(backoff.next().unwrap()).await;
+            }
+        })
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use futures::stream;
+    use vector_lib::event::{BatchNotifier, BatchStatus};
+    use vrl::btreemap;
+
+    use super::*;
+
+    fn config(command: &[&str], protocol: ExecProtocol) -> ExecConfig {
+        ExecConfig {
+            command: command.iter().map(|arg| arg.to_string()).collect(),
+            environment: None,
+            clear_environment: false,
+            working_directory: None,
+            protocol,
+            respawn_interval_secs: 1,
+            max_respawn_interval_secs: 1,
+        }
+    }
+
+    async fn run(config: &ExecConfig, events: Vec<Event>) -> Vec<Event> {
+        let exec = Box::new(Exec::new(config).unwrap());
+        exec.transform(Box::pin(stream::iter(events)))
+            .collect()
+            .await
+    }
+
+    #[test]
+    fn encodes_length_prefixed_frames() {
+        let log = LogEvent::from(btreemap! { "message" => "hello" });
+        let frame = ExecProtocol::LengthPrefixed.encode(&log).unwrap();
+
+        assert_eq!(&frame[..], b"\x00\x00\x00\x13{\"message\":\"hello\"}");
+    }
+
+    #[tokio::test]
+    async fn round_trips_events() {
+        for protocol in [ExecProtocol::Ndjson, ExecProtocol::LengthPrefixed] {
+            let events = (0..3)
+                .map(|index| Event::from(LogEvent::from(btreemap! { "index" => index })))
+                .collect::<Vec<_>>();
+            let output = run(&config(&["cat"], protocol), events).await;
+
+            let indexes = output
+                .iter()
+                .map(|event| event.as_log()["index"].clone())
+                .collect::<Vec<_>>();
+            assert_eq!(indexes, [0.into(), 1.into(), 2.into()]);
+        }
+    }
+
+    #[tokio::test]
+    async fn acknowledges_inputs_with_outputs() {
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let event = LogEvent::from(btreemap! { "message" => "hello" }).with_batch_notifier(&batch);
+        drop(batch);
+        let mut output = run(&config(&["cat"], ExecProtocol::Ndjson), vec![event.into()]).await;
+
+        // The input is only acknowledged once the output that carries its finalizers is.
+        assert!(receiver.try_recv().is_err());
+        let log = output.remove(0).into_log();
+        log.metadata().update_status(EventStatus::Delivered);
+        drop(log);
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+    }
+
+    #[tokio::test]
+    async fn rejects_inputs_when_the_process_fails() {
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let event = LogEvent::from(btreemap! { "message" => "hello" }).with_batch_notifier(&batch);
+        drop(batch);
+        let config = config(
+            &["sh", "-c", "cat >/dev/null; exit 1"],
+            ExecProtocol::Ndjson,
+        );
+        let output = run(&config, vec![event.into()]).await;
+
+        assert!(output.is_empty());
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Errored));
+    }
+
+    #[tokio::test]
+    async fn skips_invalid_output() {
+        let config = config(
+            &[
+                "sh",
+                "-c",
+                r#"cat >/dev/null; echo 'not json'; echo '{"a":1}'"#,
+            ],
+            ExecProtocol::Ndjson,
+        );
+        let output = run(&config, vec![Event::from(LogEvent::default())]).await;
+
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].as_log()["a"], 1.into());
+    }
+}
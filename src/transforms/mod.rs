@@ -13,6 +13,8 @@ pub mod aggregate;
 pub mod aws_ec2_metadata;
 #[cfg(feature = "transforms-exclusive-route")]
 mod exclusive_route;
+#[cfg(feature = "transforms-exec")]
+pub mod exec;
 #[cfg(feature = "transforms-filter")]
 pub mod filter;
 #[cfg(feature = "transforms-incremental_to_absolute")]
@@ -70,6 +70,49 @@ generated: components: sinks: postgres: configuration: {
 			}
 		}
 	}
+	bulk: {
+		description: "How batches are written to the table."
+		required:    false
+		type: string: {
+			default: "insert"
+			enum: {
+				copy: """
+					Each batch is streamed into the table with `COPY ... FROM STDIN` in CSV format, which is
+					much faster than `INSERT` for large batches.
+
+					The columns of the table are looked up before the first batch is written, so columns
+					added to the table later are not written until the sink is restarted. Fields that are
+					objects or arrays are written as JSON text, so they can only be copied into `json` and
+					`jsonb` columns, or text columns.
+					"""
+				insert: """
+					Each batch is inserted with a single `INSERT` statement, populating the rows from the
+					events with `jsonb_populate_recordset`.
+					"""
+			}
+		}
+	}
+	conflict: {
+		description: """
+			How rows that conflict with an existing row are handled.
+
+			This cannot be set together with `bulk = "copy"`, as `COPY` has no conflict handling.
+			"""
+		required: false
+		type: string: {
+			default: "error"
+			enum: {
+				error:  "A conflicting row fails the insert, and with it the whole batch."
+				ignore: "A conflicting row is skipped, and the existing row is kept as is."
+				upsert: """
+					A conflicting row updates the existing row.
+
+					Every column of the batch that is not a key column is overwritten, including columns
+					that the event does not set, which are written as they would be on insert.
+					"""
+			}
+		}
+	}
 	endpoint: {
 		description: """
 			The PostgreSQL server connection string. It can contain the username and password.
@@ -79,6 +122,34 @@ generated: components: sinks: postgres: configuration: {
 		required: true
 		type: string: {}
 	}
+	json_column: {
+		description: """
+			The `jsonb` column that whole events are inserted into.
+
+			When set, each event is written as a single JSON document into this column instead of
+			being spread across the columns of the table, so the table only needs to define this
+			column (or give the others defaults). The name is quoted as an identifier, so it is
+			case-sensitive.
+			"""
+		required: false
+		type: string: examples: ["event"]
+	}
+	key_columns: {
+		description: """
+			The columns of the primary key or unique index that identifies a row.
+
+			Required when `conflict` is `ignore` or `upsert`. They are the [conflict target][upsert]
+			of the statement, and are left unchanged by an upsert. An upsert fails its whole batch
+			if the batch has more than one row with the same key.
+
+			[upsert]: https://www.postgresql.org/docs/current/sql-insert.html#SQL-ON-CONFLICT
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: string: examples: ["id"]
+		}
+	}
 	pool_size: {
 		description: """
 			The postgres connection pool size. See [this](https://docs.rs/sqlx/latest/sqlx/struct.Pool.html#why-use-a-pool) for more
@@ -283,4 +354,104 @@ generated: components: sinks: postgres: configuration: {
 		required: true
 		type: string: {}
 	}
+	tls: {
+		description: """
+			TLS configuration for the connection.
+
+			When set, the connection requires TLS. The server certificate and hostname are verified
+			unless `verify_certificate` or `verify_hostname` are disabled. The `alpn_protocols`,
+			`key_pass`, and `server_name` options are not supported.
+			"""
+		required: false
+		type: object: options: {
+			alpn_protocols: {
+				description: """
+					Sets the list of supported ALPN protocols.
+
+					Declare the supported ALPN protocols, which are used during negotiation with a peer. They are prioritized in the order
+					that they are defined.
+					"""
+				required: false
+				type: array: items: type: string: examples: ["h2"]
+			}
+			ca_file: {
+				description: """
+					Absolute path to an additional CA certificate file.
+
+					The certificate must be in the DER or PEM (X.509) format. Additionally, the certificate can be provided as an inline string in PEM format.
+					"""
+				required: false
+				type: string: examples: ["/path/to/certificate_authority.crt"]
+			}
+			crt_file: {
+				description: """
+					Absolute path to a certificate file used to identify this server.
+
+					The certificate must be in DER, PEM (X.509), or PKCS#12 format. Additionally, the certificate can be provided as
+					an inline string in PEM format.
+
+					If this is set _and_ is not a PKCS#12 archive, `key_file` must also be set.
+					"""
+				required: false
+				type: string: examples: ["/path/to/host_certificate.crt"]
+			}
+			key_file: {
+				description: """
+					Absolute path to a private key file used to identify this server.
+
+					The key must be in DER or PEM (PKCS#8) format. Additionally, the key can be provided as an inline string in PEM format.
+					"""
+				required: false
+				type: string: examples: ["/path/to/host_certificate.key"]
+			}
+			key_pass: {
+				description: """
+					Passphrase used to unlock the encrypted key file.
+
+					This has no effect unless `key_file` is set.
+					"""
+				required: false
+				type: string: examples: ["${KEY_PASS_ENV_VAR}", "PassWord1"]
+			}
+			server_name: {
+				description: """
+					Server name to use when using Server Name Indication (SNI).
+
+					Only relevant for outgoing connections.
+					"""
+				required: false
+				type: string: examples: ["www.example.com"]
+			}
+			verify_certificate: {
+				description: """
+					Enables certificate verification. For components that create a server, this requires that the
+					client connections have a valid client certificate. For components that initiate requests,
+					this validates that the upstream has a valid certificate.
+
+					If enabled, certificates must not be expired and must be issued by a trusted
+					issuer. This verification operates in a hierarchical manner, checking that the leaf certificate (the
+					certificate presented by the client/server) is not only valid, but that the issuer of that certificate is also valid, and
+					so on, until the verification process reaches a root certificate.
+
+					Do NOT set this to `false` unless you understand the risks of not verifying the validity of certificates.
+					"""
+				required: false
+				type: bool: {}
+			}
+			verify_hostname: {
+				description: """
+					Enables hostname verification.
+
+					If enabled, the hostname used to connect to the remote host must be present in the TLS certificate presented by
+					the remote host, either as the Common Name or as an entry in the Subject Alternative Name extension.
+
+					Only relevant for outgoing connections.
+
+					Do NOT set this to `false` unless you understand the risks of not verifying the remote hostname.
+					"""
+				required: false
+				type: bool: {}
+			}
+		}
+	}
 }
@@ -0,0 +1,556 @@
+package metadata
+
+generated: components: sinks: sqlite: configuration: {
+	acknowledgements: {
+		description: """
+			Controls how acknowledgements are handled for this sink.
+
+			See [End-to-end Acknowledgements][e2e_acks] for more information on how event acknowledgement is handled.
+
+			[e2e_acks]: https://vector.dev/docs/architecture/end-to-end-acknowledgements/
+			"""
+		required: false
+		type: object: options: enabled: {
+			description: """
+				Controls whether or not end-to-end acknowledgements are enabled.
+
+				When enabled for a sink, any source that supports end-to-end
+				acknowledgements that is connected to that sink waits for events
+				to be acknowledged by **all connected sinks** before acknowledging them at the source.
+
+				Enabling or disabling acknowledgements at the sink level takes precedence over any global
+				[`acknowledgements`][global_acks] configuration.
+
+				[global_acks]: https://vector.dev/docs/reference/configuration/global-options/#acknowledgements
+				"""
+			required: false
+			type: bool: {}
+		}
+	}
+	allowed_tables: {
+		description: """
+			The tables that the sink may write to.
+
+			When set, events whose rendered table is not in this list are dropped. This is
+			recommended when `table` is templated over untrusted event fields.
+			"""
+		required: false
+		type: array: items: type: string: examples: ["logs_api"]
+	}
+	batch: {
+		description: """
+			Event batching behavior.
+
+			Each batch is written with multi-row `INSERT` statements whose columns are the union of
+			the top-level fields of the events in the batch, split to fit the placeholder limit of
+			the database, and all of them are executed in a single transaction. Fields that an event
+			does not set are written as `NULL`.
+			"""
+		required: false
+		type: object: options: {
+			max_bytes: {
+				description: """
+					The maximum size of a batch that is processed by a sink.
+
+					This is based on the uncompressed size of the batched events, before they are
+					serialized or compressed.
+					"""
+				required: false
+				type: uint: {
+					default: 10000000
+					unit:    "bytes"
+				}
+			}
+			max_events: {
+				description: "The maximum size of a batch before it is flushed."
+				required:    false
+				type: uint: {
+					default: 1000
+					unit:    "events"
+				}
+			}
+			timeout_secs: {
+				description: "The maximum age of a batch before it is flushed."
+				required:    false
+				type: float: {
+					default: 1.0
+					unit:    "seconds"
+				}
+			}
+		}
+	}
+	columns: {
+		description: """
+			The columns that are written, keyed by column name, in the order of the table.
+
+			When set, only these columns are written and all other event fields are ignored, unless
+			`overflow_column` is set, so that every batch inserts the same columns. When unset, the
+			columns are the top-level fields of the events in each batch.
+			"""
+		required: false
+		type: object: options: "*": {
+			description: "The configuration of a column."
+			required:    true
+			type: object: options: {
+				default: {
+					description: "The value written when the event does not have the field, or the field is `null`."
+					required:    false
+					type: "*": {}
+				}
+				expression: {
+					description: """
+						A [VRL][vrl] expression that the column is written from, instead of a field.
+
+						The expression is evaluated against each event, which it cannot modify, in the global
+						`timezone`. An event for which the expression fails or returns `null` is written with the
+						column default, and is not rejected ahead of the insert when the column is not nullable.
+
+						[vrl]: https://vector.dev/docs/reference/vrl
+						"""
+					required: false
+					type: string: examples: ["upcase(string!(.level))", "sha2(string!(.user_email))"]
+				}
+				field: {
+					description: """
+						The event field that the column is written from.
+
+						Defaults to the top-level field named after the column.
+						"""
+					required: false
+					type: string: examples: ["message", "kubernetes.pod_name"]
+				}
+				nullable: {
+					description: """
+						Whether the column can be written as `NULL`.
+
+						An event that has neither a value nor a default for a column that is not nullable is
+						rejected on its own, instead of failing the batch it is part of. A column that the sink
+						creates is declared `NOT NULL` when this is `false`.
+						"""
+					required: false
+					type: bool: default: true
+				}
+				type: {
+					description: """
+						The SQL type of the column, used when the sink creates the table or adds the column.
+
+						When unset, the type is inferred from the values written to the column.
+						"""
+					required: false
+					type: string: examples: ["VARCHAR(255)"]
+				}
+			}
+		}
+	}
+	conflict: {
+		description: "How rows that conflict with an existing row are handled."
+		required:    false
+		type: string: {
+			default: "error"
+			enum: {
+				error:  "A conflicting row fails the insert, and with it the whole batch."
+				ignore: "A conflicting row is skipped, and the existing row is kept as is."
+				upsert: """
+					A conflicting row updates the existing row.
+
+					Every column of the batch that is not a key column is overwritten, including columns
+					that the event does not set, which are written as they would be on insert.
+					"""
+			}
+		}
+	}
+	dead_letter_table: {
+		description: """
+			A table that rows which fail to insert on their own are written to, instead of being
+			rejected.
+
+			Requires `on_partial_failure` to be `split_batch`. Each row is written as a JSON object of
+			its columns to the `row` column, together with the table it failed to insert into, the
+			error, and the time it failed, in the `table`, `error`, and `timestamp` columns. The table
+			is created with these columns unless `schema_management` is `none`.
+			"""
+		required: false
+		type: string: examples: ["failed_rows"]
+	}
+	init_sql: {
+		description: """
+			SQL statements executed every time a database file is opened.
+
+			This is typically used to create the table, with `CREATE TABLE IF NOT EXISTS`, so that
+			newly rotated files can be written to.
+			"""
+		required: false
+		type: string: examples: ["CREATE TABLE IF NOT EXISTS logs (timestamp TEXT, host TEXT, message TEXT)"]
+	}
+	key_columns: {
+		description: """
+			The columns of the primary key or unique index that identifies a row.
+
+			Required when `conflict` is `ignore` or `upsert`. They are the [conflict target][upsert]
+			of the statement, and are left unchanged by an upsert.
+
+			[upsert]: https://www.sqlite.org/lang_upsert.html
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: string: examples: ["id"]
+		}
+	}
+	max_file_size: {
+		description: """
+			The size, in bytes, at which the database file is rotated.
+
+			Once a write leaves the file (including its write-ahead log) at or above this size, the
+			file is closed and renamed with a UTC timestamp suffix, as in
+			`events.20250101T120000000000Z.db`, and the next batch is written to a new file.
+			"""
+		required: false
+		type: uint: unit: "bytes"
+	}
+	metrics: {
+		description: """
+			How metric events are written as rows.
+
+			When set, the sink accepts metrics instead of logs. Each metric is converted into a row
+			whose fields are then written as configured by `columns`.
+			"""
+		required: false
+		type: object: options: {
+			columns: {
+				description: """
+					The value columns of the `wide` layout, keyed by column name, each with the name of the
+					metric whose value is written to it.
+					"""
+				required: false
+				type: object: options: "*": {
+					description: "The name of the metric written to the column."
+					required:    true
+					type: string: {}
+				}
+			}
+			layout: {
+				description: "The layout of the rows."
+				required:    false
+				type: string: {
+					default: "narrow"
+					enum: {
+						narrow: "Each metric is a row of `name`, `namespace`, `tags`, `value`, and `timestamp` columns."
+						wide: """
+							Metrics with the same tags and timestamp are merged into a single row of `tags` and
+							`timestamp` columns, with the value of each metric in its configured column.
+							"""
+					}
+				}
+			}
+		}
+	}
+	on_partial_failure: {
+		description: "How a batch is handled when some of its rows fail to insert."
+		required:    false
+		type: string: {
+			default: "retry_all"
+			enum: {
+				retry_all: """
+					The transaction is rolled back and the whole batch fails, to be retried if the error is
+					transient and rejected otherwise.
+					"""
+				split_batch: """
+					When the error is not transient, the batch is split in halves that are inserted
+					separately, within the same transaction, until the rows that fail on their own are
+					found. Only those rows are rejected, and all others are committed.
+					"""
+			}
+		}
+	}
+	overflow_column: {
+		description: """
+			A column that all event fields not written to any of the `columns` are collected into,
+			as a JSON object.
+
+			This keeps fields that were added to the events after the columns were configured. The
+			column is written as `NULL` for events whose fields are all mapped to columns.
+			"""
+		required: false
+		type: string: examples: ["extra"]
+	}
+	path: {
+		description: """
+			The path of the database file. The file and its parent directories are created if they
+			do not exist.
+
+			The path can contain [`strftime` specifiers][strftime], which are rendered with the
+			current UTC time every time a batch is written. A new database file is opened whenever
+			the rendered path changes, which rotates the database by date.
+
+			[strftime]: https://docs.rs/chrono/latest/chrono/format/strftime/index.html#specifiers
+			"""
+		required: true
+		type: string: examples: ["/var/lib/vector/events.db", "/var/lib/vector/events-%Y-%m-%d.db"]
+	}
+	request: {
+		description: """
+			Outbound request behavior.
+
+			SQLite allows a single writer per database, so batches are written one at a time
+			whatever the concurrency, and it defaults to `none`.
+			"""
+		required: false
+		type: object: options: {
+			adaptive_concurrency: {
+				description: """
+					Configuration of adaptive concurrency parameters.
+
+					These parameters typically do not require changes from the default, and incorrect values can lead to meta-stable or
+					unstable performance and sink behavior. Proceed with caution.
+					"""
+				required: false
+				type: object: options: {
+					decrease_ratio: {
+						description: """
+																The fraction of the current value to set the new concurrency limit when decreasing the limit.
+
+																Valid values are greater than `0` and less than `1`. Smaller values cause the algorithm to scale back rapidly
+																when latency increases.
+
+																**Note**: The new limit is rounded down after applying this ratio.
+																"""
+						required: false
+						type: float: default: 0.9
+					}
+					ewma_alpha: {
+						description: """
+																The weighting of new measurements compared to older measurements.
+
+																Valid values are greater than `0` and less than `1`.
+
+																ARC uses an exponentially weighted moving average (EWMA) of past RTT measurements as a reference to compare with
+																the current RTT. Smaller values cause this reference to adjust more slowly, which may be useful if a service has
+																unusually high response variability.
+																"""
+						required: false
+						type: float: default: 0.4
+					}
+					initial_concurrency: {
+						description: """
+																The initial concurrency limit to use. If not specified, the initial limit is 1 (no concurrency).
+
+																Datadog recommends setting this value to your service's average limit if you're seeing that it takes a
+																long time to ramp up adaptive concurrency after a restart. You can find this value by looking at the
+																`adaptive_concurrency_limit` metric.
+																"""
+						required: false
+						type: uint: default: 1
+					}
+					max_concurrency_limit: {
+						description: """
+																The maximum concurrency limit.
+
+																The adaptive request concurrency limit does not go above this bound. This is put in place as a safeguard.
+																"""
+						required: false
+						type: uint: default: 200
+					}
+					rtt_deviation_scale: {
+						description: """
+																Scale of RTT deviations which are not considered anomalous.
+
+																Valid values are greater than or equal to `0`, and reasonable values range from `1.0` to `3.0`.
+
+																When calculating the past RTT average, a secondary “deviation” value is also computed that indicates how variable
+																those values are. That deviation is used when comparing the past RTT average to the current measurements, so we
+																can ignore increases in RTT that are within an expected range. This factor is used to scale up the deviation to
+																an appropriate range. Larger values cause the algorithm to ignore larger increases in the RTT.
+																"""
+						required: false
+						type: float: default: 2.5
+					}
+				}
+			}
+			concurrency: {
+				description: """
+					Configuration for outbound request concurrency.
+
+					This can be set either to one of the below enum values or to a positive integer, which denotes
+					a fixed concurrency limit.
+					"""
+				required: false
+				type: {
+					string: {
+						default: "none"
+						enum: {
+							adaptive: """
+															Concurrency is managed by Vector's [Adaptive Request Concurrency][arc] feature.
+
+															[arc]: https://vector.dev/docs/architecture/arc/
+															"""
+							none: """
+															A fixed concurrency of 1.
+
+															Only one request can be outstanding at any given time.
+															"""
+						}
+					}
+					uint: {}
+				}
+			}
+			rate_limit_duration_secs: {
+				description: "The time window used for the `rate_limit_num` option."
+				required:    false
+				type: uint: {
+					default: 1
+					unit:    "seconds"
+				}
+			}
+			rate_limit_num: {
+				description: "The maximum number of requests allowed within the `rate_limit_duration_secs` time window."
+				required:    false
+				type: uint: {
+					default: 9223372036854775807
+					unit:    "requests"
+				}
+			}
+			retry_attempts: {
+				description: "The maximum number of retries to make for failed requests."
+				required:    false
+				type: uint: {
+					default: 9223372036854775807
+					unit:    "retries"
+				}
+			}
+			retry_initial_backoff_secs: {
+				description: """
+					The amount of time to wait before attempting the first retry for a failed request.
+
+					After the first retry has failed, the Fibonacci sequence is used to select future backoffs.
+					"""
+				required: false
+				type: uint: {
+					default: 1
+					unit:    "seconds"
+				}
+			}
+			retry_jitter_mode: {
+				description: "The jitter mode to use for retry backoff behavior."
+				required:    false
+				type: string: {
+					default: "Full"
+					enum: {
+						Full: """
+															Full jitter.
+
+															The random delay is anywhere from 0 up to the maximum current delay calculated by the backoff
+															strategy.
+
+															Incorporating full jitter into your backoff strategy can greatly reduce the likelihood
+															of creating accidental denial of service (DoS) conditions against your own systems when
+															many clients are recovering from a failure state.
+															"""
+						None: "No jitter."
+					}
+				}
+			}
+			retry_max_duration_secs: {
+				description: "The maximum amount of time to wait between retries."
+				required:    false
+				type: uint: {
+					default: 30
+					unit:    "seconds"
+				}
+			}
+			timeout_secs: {
+				description: """
+					The time a request can take before being aborted.
+
+					Datadog highly recommends that you do not lower this value below the service's internal timeout, as this could
+					create orphaned requests, pile on retries, and result in duplicate data downstream.
+					"""
+				required: false
+				type: uint: {
+					default: 60
+					unit:    "seconds"
+				}
+			}
+		}
+	}
+	schema_management: {
+		description: """
+			Whether tables are created, and columns added, to fit the events that are written.
+
+			Column types are inferred from the first non-null value of a field in a batch. Tables
+			and columns are only looked up the first time a batch needs them, so changes made to the
+			table outside of Vector are not picked up until the sink is restarted.
+			"""
+		required: false
+		type: string: {
+			default: "none"
+			enum: {
+				create: """
+					A table that does not exist is created, with a column for every field of the first batch
+					written to it.
+					"""
+				create_and_alter: """
+					Like `create`, and columns are added to an existing table whenever a batch has fields
+					that the table has no column for.
+					"""
+				none: "Tables are expected to exist with all the columns that are written."
+			}
+		}
+	}
+	table: {
+		description: """
+			The table that data is inserted into.
+
+			The name can be qualified with an attached database, as in `main.table`, and can be a
+			template over event fields. Batches are partitioned by the rendered name, which is quoted
+			as an identifier when rendered into the `INSERT` statement. Events whose rendered name has
+			a different number of `.`-separated parts than the template are dropped.
+			"""
+		required: true
+		type: string: {
+			examples: ["logs", "logs_{{ service }}"]
+			syntax: "template"
+		}
+	}
+	time_partition: {
+		description: """
+			Writes events to one table per hour or day, named after `table` with the time of the
+			event timestamp appended, such as `logs_20250101`.
+
+			`allowed_tables` is checked against the table before the time is appended.
+			"""
+		required: false
+		type: object: options: {
+			create_ahead: {
+				description: """
+					The number of following tables that are created from a table when it is first written
+					to, so that they exist before events start being written to them.
+					"""
+				required: false
+				type: uint: {
+					default:  0
+					examples: [1]
+				}
+			}
+			interval: {
+				description: "The time span covered by each table."
+				required:    true
+				type: string: {
+					enum: {
+						daily:  "One table per day, such as `logs_20250101`."
+						hourly: "One table per hour, such as `logs_2025010113`."
+					}
+				}
+			}
+		}
+	}
+	wal: {
+		description: """
+			Whether to open the database in [write-ahead logging][wal] mode.
+
+			[wal]: https://www.sqlite.org/wal.html
+			"""
+		required: false
+		type: bool: default: true
+	}
+}
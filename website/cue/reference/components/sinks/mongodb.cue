@@ -0,0 +1,80 @@
+package metadata
+
+components: sinks: mongodb: {
+	title: "MongoDB"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: true
+		auto_generated:   true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    10_000_000
+				timeout_secs: 1.0
+			}
+			request: {
+				enabled: true
+				headers: false
+			}
+			compression: enabled: false
+			encoding: enabled:    false
+			tls: enabled:         false
+			to: {
+				service: services.mongodb
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["tcp"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: generated.components.sinks.mongodb.configuration
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	how_it_works: {
+		inserting_documents: {
+			title: "Inserting documents"
+			body: """
+				Each event is inserted as a document into the rendered `collection`, with one
+				unordered `insertMany` per collection in a batch. Events without an `_id` field are
+				given one when they are batched, so that retrying a batch does not insert the same
+				events twice: documents whose given `_id` already exists in the collection are
+				skipped. Any other duplicate key, including an `_id` carried by the event, is not
+				retried and rejects the batch.
+				"""
+		}
+
+		tls: {
+			title: "TLS"
+			body: """
+				TLS is configured through the [connection string](\(urls.mongodb_connection_string_uri_format)),
+				for example with `tls=true` and `tlsCAFile`.
+				"""
+		}
+	}
+}
@@ -28,7 +28,13 @@ components: sinks: postgres: {
 			}
 			compression: enabled: false
 			encoding: enabled:    false
-			tls: enabled:         false
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+				enabled_by_scheme:      false
+			}
 			to: {
 				service: services.postgres
 				interface: {
@@ -0,0 +1,111 @@
+package metadata
+
+components: sinks: sqlite: {
+	title: "SQLite"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: true
+		auto_generated:   true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    10_000_000
+				max_events:   1000
+				timeout_secs: 1.0
+			}
+			request: {
+				enabled: true
+				headers: false
+			}
+			compression: enabled: false
+			encoding: enabled:    false
+			tls: enabled:         false
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: generated.components.sinks.sqlite.configuration
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: false
+			gauge:        true
+			histogram:    false
+			set:          true
+			summary:      false
+		}
+		traces: false
+	}
+
+	how_it_works: {
+		inserting_events: {
+			title: "Inserting events"
+			body: """
+				Each batch is written in a single transaction, with multi-row `INSERT` statements
+				whose columns are the top-level fields of the events in the batch, or the configured
+				`columns`. Fields that an event does not set are written as `NULL`.
+
+				SQLite allows a single writer per database, so batches are written one at a time.
+				For example, the following configuration writes logs to a new database file every
+				day, creating the `logs` table in each of them:
+
+				```yaml
+				sinks:
+				  sqlite:
+				    type: sqlite
+				    inputs:
+				      - my_logs
+				    path: /var/lib/vector/events-%Y-%m-%d.db
+				    table: logs
+				    init_sql: CREATE TABLE IF NOT EXISTS logs (timestamp TEXT, host TEXT, message TEXT)
+				```
+				"""
+		}
+
+		rotation: {
+			title: "Rotating database files"
+			body: """
+				A new database file is opened whenever the rendered `path` changes, and, when
+				`max_file_size` is set, once a write leaves the file at or above that size, in which
+				case the full file is renamed with a UTC timestamp suffix. `init_sql` is executed
+				every time a file is opened, so that new files have the tables that are written to.
+				"""
+		}
+
+		metrics: {
+			title: "Writing metrics"
+			body: """
+				When `metrics` is set, the sink accepts metric events instead of logs. Counters and
+				gauges are written with their value, and sets with the number of their values.
+				Distributions, histograms, and summaries are rejected.
+				"""
+		}
+
+		partial_failures: {
+			title: "Partial failures"
+			body: """
+				By default, a single row that fails to insert fails its whole batch. When
+				`on_partial_failure` is `split_batch`, the rows that fail on their own are found
+				by splitting the batch, and are rejected, or written to the `dead_letter_table`,
+				while all other rows are committed.
+				"""
+		}
+	}
+}
@@ -0,0 +1,77 @@
+package metadata
+
+components: transforms: exec: {
+	title: "Exec"
+
+	description: """
+		Passes events through a long-running external process over its standard input and output.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		shape: {}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: generated.components.transforms.exec.configuration
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	output: {
+		logs: "": {
+			description: "The events written by the process, decoded from JSON."
+		}
+	}
+
+	how_it_works: {
+		protocol: {
+			title: "Protocol"
+			body: """
+				The process is started once, when the transform starts, and is kept running. Each
+				event is written to its standard input as a JSON object, and every JSON object that
+				the process writes to its standard output becomes an event. With the default `ndjson`
+				protocol each object is a line, and with `length_prefixed` each object is preceded
+				by its length in bytes as a 4-byte big-endian integer.
+
+				The process does not have to answer every event with exactly one event: it can
+				drop events, or write several events for one, in any order. Lines or payloads
+				longer than 8 MiB are rejected.
+				"""
+		}
+
+		acknowledgements: {
+			title: "Acknowledgements"
+			body: """
+				The events written to the process are acknowledged together with the next event
+				that the process writes, which carries the metadata of the last event written to
+				the process. Events that the process drops are therefore only acknowledged once it
+				writes another event.
+				"""
+		}
+
+		respawning: {
+			title: "Respawning"
+			body: """
+				If the process exits, it is started again after `respawn_interval_secs`. The wait
+				doubles each time that the process exits without writing an event, up to
+				`max_respawn_interval_secs`. Events that the process has read but not answered
+				when it exits are reported to their sources as failed.
+				"""
+		}
+	}
+}
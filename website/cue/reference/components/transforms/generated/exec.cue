@@ -0,0 +1,85 @@
+package metadata
+
+generated: components: transforms: exec: configuration: {
+	clear_environment: {
+		description: "Whether or not to clear the environment before setting custom environment variables."
+		required:    false
+		type: bool: default: false
+	}
+	command: {
+		description: """
+			The command to run, plus any arguments required.
+
+			The process reads events from its standard input and writes the resulting events to its
+			standard output, each encoded as a JSON object and framed according to `protocol`. It may
+			write any number of events for each event that it reads, and its standard error is
+			inherited from Vector.
+
+			Each event that the process writes carries the metadata of the last event written to it.
+			The events written to the process are acknowledged together with the next event it writes,
+			so events that it filters out are only acknowledged once it writes another event.
+			"""
+		required: true
+		type: array: items: type: string: examples: ["python3", "transform.py"]
+	}
+	environment: {
+		description: """
+			Custom environment variables to set or update when running the command.
+			If a variable name already exists in the environment, its value is replaced.
+			"""
+		required: false
+		type: object: {
+			examples: [{
+				LANG: "es_ES.UTF-8"
+				PATH: "/bin:/usr/bin:/usr/local/bin"
+				TZ:   "Etc/UTC"
+			}]
+			options: "*": {
+				description: "An environment variable."
+				required:    true
+				type: string: {}
+			}
+		}
+	}
+	max_respawn_interval_secs: {
+		description: "The maximum amount of time, in seconds, to wait before respawning the process."
+		required:    false
+		type: uint: {
+			default: 60
+			unit:    "seconds"
+		}
+	}
+	protocol: {
+		description: "How events are framed on the standard input and output of the process."
+		required:    false
+		type: string: {
+			default: "ndjson"
+			enum: {
+				length_prefixed: """
+					Each event is a JSON payload, preceded by its length in bytes as a 4-byte big-endian
+					integer.
+					"""
+				ndjson: "Each event is a line of JSON."
+			}
+		}
+	}
+	respawn_interval_secs: {
+		description: """
+			The amount of time, in seconds, to wait before respawning the process after it exits.
+
+			The wait doubles each time that the process exits without writing an event, up to
+			`max_respawn_interval_secs`. Events that the process has read but not yet answered when it
+			exits are lost, and reported to their sources as failed.
+			"""
+		required: false
+		type: uint: {
+			default: 1
+			unit:    "seconds"
+		}
+	}
+	working_directory: {
+		description: "The directory in which to run the command."
+		required:    false
+		type: string: {}
+	}
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: mysql: {
+	name:     "MySQL"
+	thing:    "a \(name) database"
+	url:      urls.mysql
+	versions: null
+
+	description: "[MySQL](\(urls.mysql)) is an open source relational database management system."
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: sqlite: {
+	name:     "SQLite"
+	thing:    "a \(name) database file"
+	url:      urls.sqlite
+	versions: null
+
+	description: "[SQLite](\(urls.sqlite)) is a small, self-contained SQL database engine that stores a whole database in a single file on disk."
+}
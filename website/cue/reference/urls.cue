@@ -377,6 +377,7 @@ urls: {
 	mongodb_connection_string_uri_format:       "https://docs.mongodb.com/manual/reference/connection-string/"
 	mqtt:                                       "https://mqtt.org/"
 	musl_builder_docker_image:                  "\(vector_repo)/blob/master/scripts/ci-docker-images/builder-x86_64-unknown-linux-musl/Dockerfile"
+	mysql:                                      "https://www.mysql.com/"
 	native_proto_schema:                        "\(vector_repo)/blob/master/lib/vector-core/proto/event.proto"
 	native_json_schema:                         "\(vector_repo)/blob/master/lib/codecs/tests/data/native_encoding/schema.cue"
 	nats:                                       "https://nats.io/"
@@ -509,6 +510,7 @@ urls: {
 	splunk_hec_setup:                           "https://docs.splunk.com/Documentation/Splunk/latest/Data/UsetheHTTPEventCollector"
 	splunk_hec_metadata:                        "https://docs.splunk.com/Documentation/Splunk/latest/Data/FormateventsforHTTPEventCollector#Event_metadata"
 	specs_instrumentation:                      "\(vector_repo)/blob/master/docs/specs/instrumentation.md)"
+	sqlite:                                     "https://www.sqlite.org/"
 	standard_streams:                           "\(wikipedia)/wiki/Standard_streams"
 	statsd:                                     "\(github)/statsd/statsd"
 	statsd_multi:                               "\(github)/statsd/statsd/blob/master/docs/metric_types.md#multi-metric-packets"